@@ -0,0 +1,454 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    thread,
+};
+
+use egui::DragValue;
+use parking_lot::Mutex;
+
+use crate::{
+    save::{
+        manage::{self, SteamIdSaves},
+        npc, NpcDefinition, Save, SaveType, Season, SelectedSave, NPCS,
+    },
+    tools::{self, Params, ToolInfo},
+};
+
+/// Which saves a batch operation applies to. Unset fields match any save.
+#[derive(Debug, Clone, Default)]
+pub struct BatchFilter {
+    pub steam_id: Option<String>,
+    pub save_type: Option<SaveType>,
+    pub game_mode: Option<String>,
+    pub min_day: Option<i32>,
+    pub max_day: Option<i32>,
+}
+
+impl BatchFilter {
+    /// Whether a save matches the parts of the filter known without reading it.
+    pub fn matches_path(&self, (steam_id, save_type, _): &SelectedSave) -> bool {
+        self.steam_id.as_ref().map_or(true, |id| id == steam_id)
+            && self.save_type.map_or(true, |t| t == *save_type)
+    }
+
+    /// Whether a loaded save matches the rest of the filter.
+    pub fn matches_save(&self, save: &Save) -> bool {
+        let mode = save
+            .game_setup
+            .as_ref()
+            .and_then(|s| s.data.game_setup.mode());
+        let day = save.game_state.data.game_state.game_days;
+
+        self.game_mode.as_ref().map_or(true, |m| {
+            mode.is_some_and(|mode| mode.eq_ignore_ascii_case(m))
+        }) && self.min_day.map_or(true, |min| day >= min)
+            && self.max_day.map_or(true, |max| day <= max)
+    }
+}
+
+/// An operation that can be applied to many saves at once.
+#[derive(Debug, Clone)]
+pub enum BatchOperation {
+    Resurrect(&'static NpcDefinition),
+    ResetSeenInVillage,
+    /// Set the season a custom game starts in. Other games ignore it, so they are left alone.
+    SetStartingSeason(Season),
+    /// Apply one of the editor's tools, written as `<tool>:<param>:<param>`.
+    Tool(&'static ToolInfo, Params),
+}
+
+impl Display for BatchOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Resurrect(npc) => write!(f, "resurrect-{}", npc.name.to_lowercase()),
+            Self::ResetSeenInVillage => f.write_str("reset-seen-in-village"),
+            Self::SetStartingSeason(season) => write!(f, "set-starting-season={}", season),
+            Self::Tool(tool, params) => write!(f, "{}:{}", tool.id(), params),
+        }
+    }
+}
+
+impl FromStr for BatchOperation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((tool, params)) = s.split_once(':') {
+            let tool = tools::tool(tool).ok_or_else(|| format!("unknown tool: {}", tool))?;
+            return Ok(Self::Tool(
+                tool,
+                Params::parse(&params.split(':').collect::<Vec<_>>()),
+            ));
+        }
+
+        match s.split_once('=') {
+            Some(("set-starting-season", season)) => Ok(Self::SetStartingSeason(season.parse()?)),
+            None if s.starts_with("resurrect-") => NPCS
+                .iter()
+                .find(|npc| s["resurrect-".len()..].eq_ignore_ascii_case(npc.name))
+                .map(Self::Resurrect)
+                .ok_or_else(|| format!("unknown NPC: {}", &s["resurrect-".len()..])),
+            None if s == "reset-seen-in-village" => Ok(Self::ResetSeenInVillage),
+            _ => Err(format!("unknown operation: {}", s)),
+        }
+    }
+}
+
+impl BatchOperation {
+    /// Apply the operation to a save, describing what was done.
+    pub fn apply(&self, save: &mut Save) -> Result<String, String> {
+        match self {
+            Self::Resurrect(npc) => {
+                let resurrection = npc.resurrect(save);
+                Ok(format!("resurrected {} ({})", npc.name, resurrection))
+            }
+            Self::ResetSeenInVillage => {
+                save.save_data
+                    .data
+                    .vail_world_sim
+                    .player_stats
+                    .seen_in_village_count = 0;
+                Ok("reset seen in village count".to_owned())
+            }
+            Self::SetStartingSeason(season) => {
+                let setup = &mut save
+                    .game_setup
+                    .as_mut()
+                    .ok_or("save has no game setup")?
+                    .data
+                    .game_setup;
+
+                if !setup
+                    .mode()
+                    .is_some_and(|m| m.eq_ignore_ascii_case("custom"))
+                {
+                    return Ok(
+                        "starting season left alone, it only applies to custom games".to_owned(),
+                    );
+                }
+
+                setup.set_starting_season(*season);
+                Ok(format!("set starting season to {}", season))
+            }
+            Self::Tool(tool, params) => (tool.apply)(save, params)
+                .map(|done| format!("{}: {}", tool.name, done.join(", ")))
+                .map_err(|e| format!("{}: {}", tool.name, e)),
+        }
+    }
+}
+
+/// The outcome of a batch for a single save.
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    pub save: SelectedSave,
+    pub result: Result<Vec<String>, String>,
+    /// Whether the operations changed the save. Unchanged saves aren't written.
+    pub changed: bool,
+}
+
+impl Display for BatchResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (steam_id, save_type, name) = &self.save;
+        match &self.result {
+            Ok(done) => write!(
+                f,
+                "OK   {} {} {}: {}{}",
+                steam_id,
+                save_type,
+                name,
+                done.join(", "),
+                if self.changed { "" } else { " (unchanged)" }
+            ),
+            Err(e) => write!(f, "FAIL {} {} {}: {}", steam_id, save_type, name, e),
+        }
+    }
+}
+
+/// Apply operations to every save matching the filter. Saves are only written if every
+/// operation succeeded, something changed and this isn't a dry run, and backed up first if
+/// a backup directory is given.
+pub fn run(
+    save_dir: &Path,
+    saves: &[SteamIdSaves],
+    filter: &BatchFilter,
+    operations: &[BatchOperation],
+    backup_dir: Option<&Path>,
+    dry_run: bool,
+) -> Vec<BatchResult> {
+    let mut results = vec![];
+
+    for selected in saves.iter().flat_map(SteamIdSaves::all) {
+        if !filter.matches_path(&selected) {
+            continue;
+        }
+
+        let path = manage::save_path(save_dir, &selected);
+        let mut save = match Save::read(path.clone()) {
+            Ok(save) => save,
+            Err(e) => {
+                results.push(BatchResult {
+                    save: selected,
+                    result: Err(e.to_string()),
+                    changed: false,
+                });
+                continue;
+            }
+        };
+
+        if !filter.matches_save(&save) {
+            continue;
+        }
+
+        let fingerprint = save.fingerprint();
+        let result = operations
+            .iter()
+            .map(|op| op.apply(&mut save))
+            .collect::<Result<Vec<_>, _>>();
+        let changed = result.is_ok() && save.fingerprint() != fingerprint;
+
+        let result = result.and_then(|done| {
+            if changed && !dry_run {
+                save.write(path, backup_dir).map_err(|e| e.to_string())?;
+            }
+            Ok(done)
+        });
+
+        results.push(BatchResult {
+            save: selected,
+            result,
+            changed,
+        });
+    }
+
+    results
+}
+
+/// The GUI for running batch operations.
+#[derive(Debug, Clone, Default)]
+pub struct BatchWindow {
+    pub open: bool,
+    filter: BatchFilter,
+    /// Type ids of the NPCs to resurrect.
+    resurrect: Vec<u32>,
+    reset_seen_in_village: bool,
+    season: Option<Season>,
+    dry_run: bool,
+    results: Vec<BatchResult>,
+    /// The results of a batch running on another thread, taken into `results` once it is
+    /// done.
+    running: Option<Arc<Mutex<Option<Vec<BatchResult>>>>>,
+}
+
+impl BatchWindow {
+    /// Show the window. Returns the saves that were written to once a batch is done.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        save_dir: &Path,
+        backup_dir: Option<&Path>,
+        saves: &[SteamIdSaves],
+    ) -> Vec<SelectedSave> {
+        let mut written = vec![];
+        let mut open = self.open;
+
+        if self.running.is_some() {
+            // even with the window closed, so the results are picked up
+            ctx.request_repaint();
+        }
+        if let Some(results) = self.running.as_ref().and_then(|r| r.lock().take()) {
+            self.running = None;
+            self.results = results;
+
+            if !self.dry_run {
+                written = self
+                    .results
+                    .iter()
+                    .filter(|r| r.result.is_ok() && r.changed)
+                    .map(|r| r.save.clone())
+                    .collect();
+            }
+        }
+
+        egui::Window::new("Batch operations")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.heading("Saves");
+                self.render_filter(ui, saves);
+
+                ui.heading("Operations");
+                for npc in NPCS {
+                    let mut checked = self.resurrect.contains(&npc.type_id);
+                    if ui
+                        .checkbox(&mut checked, format!("Resurrect {}", npc.name))
+                        .changed()
+                    {
+                        self.resurrect.retain(|id| *id != npc.type_id);
+                        if checked {
+                            self.resurrect.push(npc.type_id);
+                        }
+                    }
+                }
+                ui.checkbox(
+                    &mut self.reset_seen_in_village,
+                    "Reset seen in village count",
+                );
+                ui.horizontal(|ui| {
+                    ui.label("Set starting season")
+                        .on_hover_text("Only custom games use it, other games are left alone.");
+                    option_combo(ui, "batch_season", &mut self.season, &Season::ALL);
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    // results are only counted as written if this stays as it was when run
+                    ui.add_enabled(
+                        self.running.is_none(),
+                        egui::Checkbox::new(&mut self.dry_run, "Dry run"),
+                    );
+
+                    let operations = self.operations();
+                    if ui
+                        .add_enabled(
+                            !operations.is_empty() && self.running.is_none(),
+                            egui::Button::new("Run"),
+                        )
+                        .clicked()
+                    {
+                        self.run_async(save_dir, backup_dir, saves, operations);
+                    }
+
+                    if self.running.is_some() {
+                        ui.spinner();
+                    }
+                });
+
+                if !self.results.is_empty() {
+                    let failed = self.results.iter().filter(|r| r.result.is_err()).count();
+                    ui.label(format!(
+                        "{} saves succeeded, {} failed.",
+                        self.results.len() - failed,
+                        failed
+                    ));
+
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for result in self.results.iter() {
+                                ui.label(result.to_string());
+                            }
+                        });
+                }
+            });
+
+        self.open = open;
+        written
+    }
+
+    /// Run a batch on another thread, as reading and writing every save takes a while.
+    fn run_async(
+        &mut self,
+        save_dir: &Path,
+        backup_dir: Option<&Path>,
+        saves: &[SteamIdSaves],
+        operations: Vec<BatchOperation>,
+    ) {
+        let running = Arc::new(Mutex::new(None));
+        self.running = Some(Arc::clone(&running));
+
+        let save_dir = save_dir.to_owned();
+        let backup_dir = backup_dir.map(PathBuf::from);
+        let saves = saves.to_vec();
+        let filter = self.filter.clone();
+        let dry_run = self.dry_run;
+        thread::spawn(move || {
+            let results = run(
+                &save_dir,
+                &saves,
+                &filter,
+                &operations,
+                backup_dir.as_deref(),
+                dry_run,
+            );
+            *running.lock() = Some(results);
+        });
+    }
+
+    fn render_filter(&mut self, ui: &mut egui::Ui, saves: &[SteamIdSaves]) {
+        let filter = &mut self.filter;
+
+        egui::Grid::new("batch_filter")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Steam ID");
+                let steam_ids = saves.iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+                option_combo(ui, "batch_steam_id", &mut filter.steam_id, &steam_ids);
+                ui.end_row();
+
+                ui.label("Save type");
+                option_combo(ui, "batch_save_type", &mut filter.save_type, &SaveType::ALL);
+                ui.end_row();
+
+                ui.label("Game mode");
+                let mut mode = filter.game_mode.clone().unwrap_or_default();
+                if ui.text_edit_singleline(&mut mode).changed() {
+                    filter.game_mode = Some(mode).filter(|m| !m.is_empty());
+                }
+                ui.end_row();
+
+                for (label, day) in [
+                    ("From day", &mut filter.min_day),
+                    ("To day", &mut filter.max_day),
+                ] {
+                    ui.label(label);
+                    ui.horizontal(|ui| {
+                        let mut enabled = day.is_some();
+                        if ui.checkbox(&mut enabled, "").changed() {
+                            *day = enabled.then_some(0);
+                        }
+                        if let Some(day) = day {
+                            ui.add(DragValue::new(day).clamp_range(0..=i32::MAX));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn operations(&self) -> Vec<BatchOperation> {
+        let mut operations = vec![];
+
+        operations.extend(
+            self.resurrect
+                .iter()
+                .filter_map(|id| npc(*id))
+                .map(BatchOperation::Resurrect),
+        );
+        if self.reset_seen_in_village {
+            operations.push(BatchOperation::ResetSeenInVillage);
+        }
+        if let Some(season) = self.season {
+            operations.push(BatchOperation::SetStartingSeason(season));
+        }
+
+        operations
+    }
+}
+
+/// A combo box for an optional value, where `None` means any.
+fn option_combo<T: Clone + PartialEq + Display>(
+    ui: &mut egui::Ui,
+    id: &str,
+    value: &mut Option<T>,
+    choices: &[T],
+) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(value.as_ref().map_or("Any".to_owned(), T::to_string))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(value, None, "Any");
+            for choice in choices {
+                ui.selectable_value(value, Some(choice.clone()), choice.to_string());
+            }
+        });
+}
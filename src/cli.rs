@@ -0,0 +1,719 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+
+use crate::{
+    batch::{self, BatchFilter, BatchOperation},
+    history,
+    patch::Patch,
+    repair::{self, Repair},
+    save::{export, json, manage, path::Path as SavePath, Save, SaveType, SelectedSave},
+    script::Script,
+    tools::{self, Params, TOOLS},
+    validate::{self, Severity},
+};
+
+/// Save tools for Sons Of The Forest. Run without a command to open the editor.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Cli {
+    /// The game's save directory. Defaults to the current user's.
+    #[arg(long, global = true)]
+    pub save_dir: Option<PathBuf>,
+
+    /// Back up each save into this directory before writing it. Saves aren't backed up
+    /// unless this is given.
+    #[arg(long, global = true)]
+    pub backup_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Apply operations to every save matching a filter.
+    Batch {
+        /// Only saves of this Steam ID.
+        #[arg(long)]
+        steam_id: Option<String>,
+
+        /// Only saves of this type (singleplayer, multiplayer or multiplayer-client).
+        #[arg(long = "type")]
+        save_type: Option<SaveType>,
+
+        /// Only saves in this game mode, e.g. normal or custom.
+        #[arg(long)]
+        mode: Option<String>,
+
+        /// Only saves at or after this in-game day.
+        #[arg(long)]
+        min_day: Option<i32>,
+
+        /// Only saves at or before this in-game day.
+        #[arg(long)]
+        max_day: Option<i32>,
+
+        /// Report what would change without writing any save.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Operations to apply: resurrect-<npc> (e.g. resurrect-kelvin or
+        /// resurrect-virginia), reset-seen-in-village,
+        /// set-starting-season=<spring|summer|autumn|winter> (custom games only), or a tool
+        /// and its parameters as <tool>:<param>:<param> (e.g. ziplines:delete-broken).
+        #[arg(required = true)]
+        operations: Vec<BatchOperation>,
+    },
+    /// Apply a JSON or TOML patch file to one or more save folders.
+    ApplyPatch {
+        /// The patch file.
+        patch: PathBuf,
+
+        /// Save folders to patch.
+        #[arg(required = true)]
+        saves: Vec<PathBuf>,
+
+        /// Report what would change without writing any save.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export a save folder as readable JSON, with nested JSON expanded.
+    Export {
+        /// The save folder to export.
+        save: PathBuf,
+
+        /// The directory to export to.
+        to: PathBuf,
+    },
+    /// Import a directory made by `export` back into a save folder.
+    Import {
+        /// The exported directory.
+        from: PathBuf,
+
+        /// The save folder to write.
+        save: PathBuf,
+    },
+    /// Copy a save folder to another save id of the same Steam ID and save type.
+    Clone {
+        /// The save folder to copy.
+        save: PathBuf,
+
+        /// The new save id. Defaults to an unused one.
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Give a save folder another save id.
+    Rename {
+        /// The save folder to rename.
+        save: PathBuf,
+
+        /// The new save id.
+        id: String,
+    },
+    /// Delete a save folder.
+    Delete {
+        /// The save folder to delete.
+        save: PathBuf,
+    },
+    /// Copy a save folder into the other save type's folder, converting a singleplayer save
+    /// to a multiplayer host save or back.
+    Convert {
+        /// The save folder to convert.
+        save: PathBuf,
+
+        /// The save type to convert to (singleplayer or multiplayer).
+        to: SaveType,
+
+        /// Report what would be done without copying the save.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Copy a save folder to another Steam ID, rewriting the Steam ID wherever it is
+    /// embedded in the save.
+    Transfer {
+        /// The save folder to copy.
+        save: PathBuf,
+
+        /// The Steam ID to copy the save to.
+        steam_id: String,
+
+        /// Report what would be done without copying the save.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check save folders for problems.
+    Validate {
+        /// Save folders to check.
+        #[arg(required = true)]
+        saves: Vec<PathBuf>,
+    },
+    /// Fix common corruption in save folders.
+    Repair {
+        /// Save folders to repair.
+        #[arg(required = true)]
+        saves: Vec<PathBuf>,
+
+        /// Only make these repairs: nan-stats, missing-companions, conflicting-flags or
+        /// truncated-files. Defaults to all but conflicting-flags, which changes whether a
+        /// companion is dead.
+        #[arg(long = "only")]
+        repairs: Vec<Repair>,
+
+        /// Report what would be fixed without writing any save.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print every value of a save matching a path, e.g. `actor[?TypeId==9].Stats`.
+    Query {
+        /// The path to query.
+        path: String,
+
+        /// The save folder to query.
+        save: PathBuf,
+    },
+    /// List the editor's tools, with their status for a save folder if given.
+    Tools {
+        /// The save folder to report on.
+        save: Option<PathBuf>,
+    },
+    /// Apply one of the editor's tools to a save folder. Run `tools` to see each tool's
+    /// parameters.
+    Tool {
+        /// The tool, e.g. companions or player-stats.
+        tool: String,
+
+        /// The save folder to apply the tool to.
+        save: PathBuf,
+
+        /// The tool's parameters, as key=value or just key.
+        params: Vec<String>,
+
+        /// Report what would change without writing the save.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Run a Rhai script on a save folder, reporting every changed value.
+    Run {
+        /// The script file.
+        script: PathBuf,
+
+        /// The save folder to run the script on.
+        save: PathBuf,
+
+        /// Report what would change without writing the save.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List a save folder's history: the save as it is now (0), then the other slots of its
+    /// world, such as the game's autosaves, and its backups in `--backup-dir`, newest first.
+    History {
+        /// The save folder.
+        save: PathBuf,
+
+        /// Print every value that changed between two points, e.g. `--diff 1 0`.
+        #[arg(long, num_args = 2, value_names = ["FROM", "TO"])]
+        diff: Option<Vec<usize>>,
+
+        /// Roll the save back to a slot or backup. The current files are backed up first if
+        /// `--backup-dir` is given.
+        #[arg(long, value_name = "SNAPSHOT", conflicts_with = "diff")]
+        rollback: Option<usize>,
+    },
+}
+
+/// Run a command line command.
+pub fn run(command: Command, save_dir: Option<PathBuf>, backup_dir: Option<PathBuf>) -> ExitCode {
+    let backup_dir = backup_dir.as_deref();
+    let find_save_dir = || match save_dir.clone().or_else(manage::default_save_dir) {
+        Some(dir) if dir.is_dir() => Some(dir),
+        _ => {
+            eprintln!("unable to find Sons Of The Forest save data, pass --save-dir");
+            None
+        }
+    };
+
+    match command {
+        Command::Batch {
+            steam_id,
+            save_type,
+            mode,
+            min_day,
+            max_day,
+            dry_run,
+            operations,
+        } => {
+            let filter = BatchFilter {
+                steam_id,
+                save_type,
+                game_mode: mode,
+                min_day,
+                max_day,
+            };
+
+            let Some(save_dir) = find_save_dir() else {
+                return ExitCode::FAILURE;
+            };
+
+            let saves = manage::scan_saves(&save_dir);
+            let results = batch::run(&save_dir, &saves, &filter, &operations, backup_dir, dry_run);
+
+            for result in results.iter() {
+                println!("{}", result);
+            }
+
+            let failed = results.iter().filter(|r| r.result.is_err()).count();
+            println!(
+                "{} saves succeeded, {} failed{}.",
+                results.len() - failed,
+                failed,
+                if dry_run { " (dry run)" } else { "" }
+            );
+
+            if failed == 0 {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Command::ApplyPatch {
+            patch,
+            saves,
+            dry_run,
+        } => apply_patch(&patch, &saves, backup_dir, dry_run),
+        Command::Query { path, save } => query(&path, save),
+        Command::Tools { save } => list_tools(save),
+        Command::Tool {
+            tool,
+            save,
+            params,
+            dry_run,
+        } => apply_tool(&tool, save, &Params::parse(&params), backup_dir, dry_run),
+        Command::Run {
+            script,
+            save,
+            dry_run,
+        } => run_script(&script, save, backup_dir, dry_run),
+        Command::History {
+            save,
+            diff,
+            rollback,
+        } => save_history(&save, backup_dir, diff.as_deref(), rollback),
+        Command::Clone { save, to } => manage_save(&save, |save_dir, selected| {
+            let target = to
+                .as_deref()
+                .map(|to| (selected.0.as_str(), selected.1, to));
+            manage::clone_save(save_dir, selected, target)
+                .map(|(_, _, name)| format!("Copied save {} to {}.", selected.2, name))
+        }),
+        Command::Rename { save, id } => manage_save(&save, |save_dir, selected| {
+            manage::rename_save(save_dir, selected, &id)
+                .map(|_| format!("Renamed save {} to {}.", selected.2, id))
+        }),
+        Command::Delete { save } => manage_save(&save, |save_dir, selected| {
+            manage::delete_save(save_dir, selected).map(|_| format!("Deleted save {}.", selected.2))
+        }),
+        Command::Convert { save, to, dry_run } => manage_save(&save, |save_dir, selected| {
+            manage::convert_save(save_dir, selected, to, dry_run).map(|report| report.to_string())
+        }),
+        Command::Transfer {
+            save,
+            steam_id,
+            dry_run,
+        } => manage_save(&save, |save_dir, selected| {
+            manage::transfer_save(save_dir, selected, &steam_id, dry_run)
+                .map(|report| report.to_string())
+        }),
+        Command::Validate { saves } => validate(&saves),
+        Command::Repair {
+            saves,
+            mut repairs,
+            dry_run,
+        } => {
+            if repairs.is_empty() {
+                repairs = Repair::DEFAULT.to_vec();
+            }
+            repair(&saves, &repairs, backup_dir, dry_run)
+        }
+        Command::Export { save, to } => report_files("exported", export::export_save(&save, &to)),
+        Command::Import { from, save } => {
+            report_files("imported", export::import_save(&from, &save))
+        }
+    }
+}
+
+/// Run a save management operation on a save folder, which must be in the game's folder
+/// layout so the save it is can be told from its path.
+fn manage_save(
+    path: &Path,
+    operation: impl FnOnce(&Path, &SelectedSave) -> io::Result<String>,
+) -> ExitCode {
+    let Some((save_dir, selected)) = manage::locate_save(path) else {
+        eprintln!(
+            "{} isn't a save folder (<steam id>/<save type>/<save id>)",
+            path.display()
+        );
+        return ExitCode::FAILURE;
+    };
+
+    match operation(&save_dir, &selected) {
+        Ok(report) => {
+            println!("{}", report.trim_end());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Check each save folder, failing if any has errors.
+fn validate(saves: &[PathBuf]) -> ExitCode {
+    let mut errors = 0;
+
+    for path in saves {
+        let issues = validate::validate_folder(path);
+        let save_errors = issues
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .count();
+
+        println!(
+            "{}: {} errors, {} warnings",
+            path.display(),
+            save_errors,
+            issues.len() - save_errors
+        );
+        for issue in issues.iter() {
+            println!("  {}", issue);
+        }
+
+        errors += save_errors;
+    }
+
+    if errors == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Repair each save folder, recovering truncated files before loading it.
+fn repair(
+    saves: &[PathBuf],
+    repairs: &[Repair],
+    backup_dir: Option<&Path>,
+    dry_run: bool,
+) -> ExitCode {
+    let mut failed = 0;
+
+    for path in saves {
+        println!("{}:", path.display());
+
+        let result = repair_folder(path, repairs, backup_dir, dry_run);
+
+        if let Err(e) = result {
+            println!("  failed: {}", e);
+            failed += 1;
+        }
+    }
+
+    println!(
+        "{} saves repaired, {} failed{}.",
+        saves.len() - failed,
+        failed,
+        if dry_run { " (dry run)" } else { "" }
+    );
+
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn repair_folder(
+    path: &Path,
+    repairs: &[Repair],
+    backup_dir: Option<&Path>,
+    dry_run: bool,
+) -> Result<(), String> {
+    // recovered files are printed even if the save still can't be read
+    if repairs.contains(&Repair::TruncatedFiles) {
+        for fix in repair::recover_files(path, backup_dir, dry_run).map_err(|e| e.to_string())? {
+            println!("  {}", fix);
+        }
+    }
+
+    let mut save = Save::read(path.to_owned()).map_err(|e| e.to_string())?;
+    let fixes = repair::repair_save(&mut save, repairs);
+    for fix in fixes.iter() {
+        println!("  {}", fix);
+    }
+
+    if !dry_run && fixes.iter().any(|f| f.applied) {
+        save.write(path.to_owned(), backup_dir)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn report_files(action: &str, result: io::Result<usize>) -> ExitCode {
+    match result {
+        Ok(count) => {
+            println!("{} {} files.", action, count);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Print the values of a save matching a path.
+fn query(path: &str, save: PathBuf) -> ExitCode {
+    let result = SavePath::parse(path).and_then(|path| {
+        let save = Save::read(save).map_err(|e| e.to_string())?;
+        let document = save.to_document();
+
+        Ok(path
+            .query(&document)
+            .into_iter()
+            .map(|(pointer, value)| (pointer, serde_json::to_string_pretty(value).unwrap()))
+            .collect::<Vec<_>>())
+    });
+
+    match result {
+        Ok(matches) => {
+            for (pointer, value) in matches.iter() {
+                println!("{}\n{}", pointer, value);
+            }
+
+            println!("{} matches.", matches.len());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// List every tool, with its status if a save folder is given.
+fn list_tools(path: Option<PathBuf>) -> ExitCode {
+    let save = match path.map(Save::read).transpose() {
+        Ok(save) => save,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for tool in TOOLS {
+        println!("{:<14} {}", tool.id(), tool.description);
+        if let Some(status) = save.as_ref().and_then(tool.status) {
+            println!("{:<14} {}", "", status);
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Apply a tool to a save folder, reporting what it did and every changed value.
+fn apply_tool(
+    tool: &str,
+    path: PathBuf,
+    params: &Params,
+    backup_dir: Option<&Path>,
+    dry_run: bool,
+) -> ExitCode {
+    let result = tools::tool(tool)
+        .ok_or_else(|| format!("unknown tool: {}", tool))
+        .and_then(|tool| {
+            let mut save = Save::read(path.clone()).map_err(|e| e.to_string())?;
+            let before = save.to_document();
+
+            let done = (tool.apply)(&mut save, params)?;
+            let changes = json::diff(&before, &save.to_document());
+            if !dry_run && !changes.is_empty() {
+                save.write(path.clone(), backup_dir)
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok((done, changes))
+        });
+
+    match result {
+        Ok((done, changes)) => {
+            for line in done.iter() {
+                println!("{}", line);
+            }
+            for change in changes.iter() {
+                println!("{}", change);
+            }
+
+            println!(
+                "{} changes{}.",
+                changes.len(),
+                if dry_run { " (dry run)" } else { "" }
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run a script on a save folder, printing its output and every changed value.
+fn run_script(script: &Path, path: PathBuf, backup_dir: Option<&Path>, dry_run: bool) -> ExitCode {
+    let result = Script::read(script).and_then(|script| {
+        let mut save = Save::read(path.clone()).map_err(|e| e.to_string())?;
+        let result = script.run(&mut save, dry_run);
+
+        for line in script.take_output() {
+            println!("{}", line);
+        }
+
+        let changes = result?;
+        if !dry_run && !changes.is_empty() {
+            save.write(path.clone(), backup_dir)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(changes)
+    });
+
+    match result {
+        Ok(changes) => {
+            for change in changes.iter() {
+                println!("{}", change);
+            }
+
+            println!(
+                "{} changes{}.",
+                changes.len(),
+                if dry_run { " (dry run)" } else { "" }
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// List a save folder's history, or diff or roll back to points in it.
+fn save_history(
+    path: &Path,
+    backup_dir: Option<&Path>,
+    diff: Option<&[usize]>,
+    rollback: Option<usize>,
+) -> ExitCode {
+    let snapshots = history::timeline(path, backup_dir);
+    let snapshot = |i: usize| {
+        snapshots
+            .get(i)
+            .ok_or_else(|| format!("no snapshot {}, the save has {}", i, snapshots.len()))
+    };
+
+    let result = match (diff, rollback) {
+        (Some(&[from, to]), _) => snapshot(from).and_then(|from| {
+            let to = snapshot(to)?;
+            let changes = history::diff(from, to)?;
+
+            println!("{} to {}", from.name(), to.name());
+            for change in changes.iter() {
+                println!("{}", change);
+            }
+            println!("{} changes.", changes.len());
+            Ok(())
+        }),
+        (_, Some(i)) => snapshot(i).and_then(|snapshot| {
+            history::rollback(backup_dir, path, snapshot).map_err(|e| e.to_string())?;
+            println!("Rolled back to {}.", snapshot.name());
+            Ok(())
+        }),
+        _ => {
+            for (i, snapshot) in snapshots.iter().enumerate() {
+                println!("{:>2} {}", i, snapshot);
+            }
+            Ok(())
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Apply a patch file to each save folder, reporting every changed value.
+pub(crate) fn apply_patch(
+    patch: &Path,
+    saves: &[PathBuf],
+    backup_dir: Option<&Path>,
+    dry_run: bool,
+) -> ExitCode {
+    let patch = match Patch::read(patch) {
+        Ok(patch) => patch,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(description) = patch.description.as_ref() {
+        println!("{}", description);
+    }
+
+    let mut failed = 0;
+    for path in saves {
+        let result = Save::read(path.clone())
+            .map_err(|e| e.to_string())
+            .and_then(|mut save| {
+                let changes = patch.apply(&mut save)?;
+                if !dry_run && !changes.is_empty() {
+                    save.write(path.clone(), backup_dir)
+                        .map_err(|e| e.to_string())?;
+                }
+                Ok(changes)
+            });
+
+        match result {
+            Ok(changes) => {
+                println!("OK   {} ({} changes)", path.display(), changes.len());
+                for change in changes {
+                    println!("     {}", change);
+                }
+            }
+            Err(e) => {
+                println!("FAIL {}: {}", path.display(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "{} saves succeeded, {} failed{}.",
+        saves.len() - failed,
+        failed,
+        if dry_run { " (dry run)" } else { "" }
+    );
+
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,464 @@
+//! A save's history: its current files, the other slots of its world, such as the game's
+//! autosaves, and the backups taken before each write, when backups are kept.
+
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+};
+
+use chrono::{DateTime, Local};
+use egui::{Button, RichText};
+use parking_lot::Mutex;
+
+use crate::save::{
+    json::{self, Change},
+    manage, Save, SelectedSave, NPCS,
+};
+
+/// Where a snapshot's files are.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// The save itself.
+    Current,
+    /// Another slot of the same world, by its save id.
+    Slot(String),
+    /// One of the save's backups.
+    Backup,
+}
+
+/// The save at one point in its history.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// The folder holding the snapshot's files.
+    pub path: PathBuf,
+    pub source: Source,
+    /// When the save or slot was last written, or when the backup was taken.
+    pub time: Option<DateTime<Local>>,
+    pub summary: Result<Summary, String>,
+}
+
+/// What a snapshot looks like in game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub game_days: i32,
+    pub game_hours: i32,
+    pub game_minutes: i32,
+    /// Each companion's name and whether they are alive.
+    pub companions: Vec<(&'static str, bool)>,
+    /// The player's kills across every type of creature.
+    pub kills: i32,
+    pub cut_trees: i32,
+    pub seen_in_village_count: i32,
+}
+
+impl Summary {
+    fn new(save: &Save) -> Self {
+        let state = &save.game_state.data.game_state;
+        let world = &save.save_data.data.vail_world_sim;
+
+        Self {
+            game_days: state.game_days,
+            game_hours: state.game_hours,
+            game_minutes: state.game_minutes,
+            companions: NPCS
+                .iter()
+                .map(|npc| (npc.name, !npc.is_dead(save)))
+                .collect(),
+            kills: world.kill_stats_list.iter().map(|k| k.player_killed).sum(),
+            cut_trees: world.player_stats.cut_trees,
+            seen_in_village_count: world.player_stats.seen_in_village_count,
+        }
+    }
+
+    /// The in-game time, e.g. `day 12 08:30`.
+    pub fn game_time(&self) -> String {
+        format!(
+            "day {} {:02}:{:02}",
+            self.game_days, self.game_hours, self.game_minutes
+        )
+    }
+
+    /// Each companion's status, e.g. `Kelvin alive, Virginia dead`.
+    pub fn companion_status(&self) -> String {
+        self.companions
+            .iter()
+            .map(|(name, alive)| format!("{} {}", name, if *alive { "alive" } else { "dead" }))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {}, {} kills, {} trees cut, seen in village {} times",
+            self.game_time(),
+            self.companion_status(),
+            self.kills,
+            self.cut_trees,
+            self.seen_in_village_count
+        )
+    }
+}
+
+impl Snapshot {
+    fn read(path: PathBuf, source: Source, time: Option<DateTime<Local>>) -> Self {
+        let summary = Save::read(path.clone())
+            .map(|save| Summary::new(&save))
+            .map_err(|e| e.to_string());
+
+        Self {
+            path,
+            source,
+            time,
+            summary,
+        }
+    }
+
+    /// A short name for the snapshot, e.g. `backup 2023-03-01 18:30:00`.
+    pub fn name(&self) -> String {
+        let time = self.time.map_or_else(
+            || "at an unknown time".to_owned(),
+            |t| t.format("%Y-%m-%d %H:%M:%S").to_string(),
+        );
+        match &self.source {
+            Source::Current => format!("current {}", time),
+            Source::Slot(id) => format!("slot {} {}", id, time),
+            Source::Backup => format!("backup {}", time),
+        }
+    }
+}
+
+impl Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.summary {
+            Ok(summary) => write!(f, "{}: {}", self.name(), summary),
+            Err(e) => write!(f, "{}: unreadable, {}", self.name(), e),
+        }
+    }
+}
+
+/// A save's history: the save as it is now, then the other slots of its world and its
+/// backups in a backup directory if one is given, newest first.
+pub fn timeline(path: &Path, backup_dir: Option<&Path>) -> Vec<Snapshot> {
+    let mut others = manage::world_slots(path)
+        .into_iter()
+        .map(|slot| {
+            let id = slot
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let time = modified(&slot);
+            Snapshot::read(slot, Source::Slot(id), time)
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(backup_dir) = backup_dir {
+        others.extend(manage::backups(backup_dir, path).into_iter().map(|backup| {
+            let time = manage::backup_time(&backup);
+            Snapshot::read(backup, Source::Backup, time)
+        }));
+    }
+    others.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.time));
+
+    let mut snapshots = vec![Snapshot::read(
+        path.to_owned(),
+        Source::Current,
+        modified(path),
+    )];
+    snapshots.extend(others);
+    snapshots
+}
+
+/// When a save folder was last written.
+fn modified(path: &Path) -> Option<DateTime<Local>> {
+    // the save selector shows the folder's modified time, which rewriting a file in
+    // place doesn't change, so the newest of it and its files is used
+    let files = fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok());
+
+    fs::metadata(path)
+        .into_iter()
+        .chain(files)
+        .filter_map(|m| m.modified().ok())
+        .max()
+        .map(DateTime::<Local>::from)
+}
+
+/// Every value that changed going from one snapshot to another.
+pub fn diff(from: &Snapshot, to: &Snapshot) -> Result<Vec<Change>, String> {
+    let read = |snapshot: &Snapshot| {
+        Save::read(snapshot.path.clone())
+            .map(|save| save.to_document())
+            .map_err(|e| format!("{}: {}", snapshot.name(), e))
+    };
+
+    Ok(json::diff(&read(from)?, &read(to)?))
+}
+
+/// Put a save back the way it was at a backup in a backup directory or another slot of its
+/// world. Rolling back to a slot backs up the current files first if there is a backup
+/// directory.
+pub fn rollback(backup_dir: Option<&Path>, path: &Path, snapshot: &Snapshot) -> io::Result<()> {
+    match (&snapshot.source, backup_dir) {
+        (Source::Current, _) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the save is already at this point",
+        )),
+        (Source::Slot(_), _) => manage::restore_slot(backup_dir, path, &snapshot.path),
+        (Source::Backup, Some(backup_dir)) => {
+            manage::restore_backup(backup_dir, path, &snapshot.path)
+        }
+        (Source::Backup, None) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "rolling back to a backup needs the backup directory",
+        )),
+    }
+}
+
+/// The GUI for browsing a save's history.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryWindow {
+    pub open: bool,
+    save: Option<SelectedSave>,
+    /// Where backups are kept, if they are.
+    backup_dir: Option<PathBuf>,
+    snapshots: Vec<Snapshot>,
+    /// The timeline being read on another thread, taken into `snapshots` once it is.
+    loading: Option<Arc<Mutex<Option<Vec<Snapshot>>>>>,
+    /// The snapshots to compare, from and to.
+    compare: (usize, usize),
+    diff: Option<Result<Vec<Change>, String>>,
+    /// A snapshot awaiting confirmation before rolling back to it.
+    pending_rollback: Option<usize>,
+    status: Option<Result<String, String>>,
+}
+
+impl HistoryWindow {
+    /// Open the window on a save's history.
+    pub fn show_save(
+        &mut self,
+        save_dir: &Path,
+        backup_dir: Option<PathBuf>,
+        selected: SelectedSave,
+    ) {
+        // reading every snapshot takes a while, so it is done off the UI thread
+        let loading = Arc::new(Mutex::new(None));
+        let path = manage::save_path(save_dir, &selected);
+        let thread_loading = Arc::clone(&loading);
+        let thread_backup_dir = backup_dir.clone();
+        thread::spawn(move || {
+            *thread_loading.lock() = Some(timeline(&path, thread_backup_dir.as_deref()));
+        });
+
+        *self = Self {
+            open: true,
+            loading: Some(loading),
+            save: Some(selected),
+            backup_dir,
+            // compare the latest backup to now
+            compare: (1, 0),
+            ..Default::default()
+        };
+    }
+
+    /// Show the window. Returns the save if it was rolled back.
+    pub fn show(&mut self, ctx: &egui::Context, save_dir: &Path) -> Option<SelectedSave> {
+        let selected = self.save.clone()?;
+        let path = manage::save_path(save_dir, &selected);
+        let mut rolled_back = None;
+        let mut open = self.open;
+
+        if let Some(snapshots) = self.loading.as_ref().and_then(|l| l.lock().take()) {
+            self.snapshots = snapshots;
+            self.loading = None;
+        }
+
+        egui::Window::new("Save history")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} save {} for {}",
+                        selected.1, selected.2, selected.0
+                    ));
+                    if ui.button("Refresh").clicked() {
+                        self.show_save(save_dir, self.backup_dir.clone(), selected.clone());
+                    }
+                });
+
+                if self.backup_dir.is_none() {
+                    ui.label(
+                        "Backups are off, start the tool with --backup-dir to keep a backup \
+                         before each write.",
+                    );
+                }
+
+                if self.loading.is_some() {
+                    ui.spinner();
+                    return;
+                }
+
+                self.render_timeline(ui);
+
+                ui.separator();
+                self.render_diff(ui);
+
+                if let Some(i) = self.pending_rollback {
+                    ui.separator();
+                    ui.label(format!(
+                        "Roll back to {}? {}",
+                        self.snapshots[i].name(),
+                        if self.backup_dir.is_some() {
+                            "The current files are backed up first."
+                        } else {
+                            "The current files are lost, as backups are off."
+                        }
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Roll back").clicked() {
+                            self.pending_rollback = None;
+                            self.status = Some(
+                                rollback(self.backup_dir.as_deref(), &path, &self.snapshots[i])
+                                    .map(|_| {
+                                        format!("Rolled back to {}.", self.snapshots[i].name())
+                                    })
+                                    .map_err(|e| e.to_string()),
+                            );
+
+                            if self.status.as_ref().is_some_and(Result::is_ok) {
+                                let status = self.status.take();
+                                self.show_save(save_dir, self.backup_dir.clone(), selected.clone());
+                                self.status = status;
+                                rolled_back = Some(selected.clone());
+                            }
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.pending_rollback = None;
+                        }
+                    });
+                }
+
+                match &self.status {
+                    Some(Ok(status)) => {
+                        ui.label(status);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    None => (),
+                }
+            });
+
+        self.open = open;
+        rolled_back
+    }
+
+    fn render_timeline(&mut self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical()
+            .id_source("history_timeline")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("history_timeline_grid")
+                    .num_columns(9)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in [
+                            "From",
+                            "To",
+                            "Snapshot",
+                            "Game time",
+                            "Companions",
+                            "Kills",
+                            "Trees cut",
+                            "Seen in village",
+                            "",
+                        ] {
+                            ui.label(RichText::new(header).strong());
+                        }
+                        ui.end_row();
+
+                        for (i, snapshot) in self.snapshots.iter().enumerate() {
+                            ui.radio_value(&mut self.compare.0, i, "");
+                            ui.radio_value(&mut self.compare.1, i, "");
+                            ui.label(snapshot.name());
+
+                            match &snapshot.summary {
+                                Ok(summary) => {
+                                    ui.label(summary.game_time());
+                                    ui.label(summary.companion_status());
+                                    ui.label(summary.kills.to_string());
+                                    ui.label(summary.cut_trees.to_string());
+                                    ui.label(summary.seen_in_village_count.to_string());
+                                }
+                                Err(e) => {
+                                    ui.colored_label(ui.visuals().error_fg_color, "Unreadable")
+                                        .on_hover_text(e);
+                                    for _ in 0..4 {
+                                        ui.label("");
+                                    }
+                                }
+                            }
+
+                            if snapshot.source != Source::Current
+                                && ui
+                                    .add_enabled(
+                                        self.pending_rollback.is_none(),
+                                        Button::new("Roll back"),
+                                    )
+                                    .clicked()
+                            {
+                                self.pending_rollback = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn render_diff(&mut self, ui: &mut egui::Ui) {
+        let (from, to) = self.compare;
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    from != to && to < self.snapshots.len() && from < self.snapshots.len(),
+                    Button::new("Compare"),
+                )
+                .on_hover_text("List every value that changed between the two snapshots.")
+                .clicked()
+            {
+                self.diff = Some(diff(&self.snapshots[from], &self.snapshots[to]));
+            }
+
+            if let (Some(from), Some(to)) = (self.snapshots.get(from), self.snapshots.get(to)) {
+                ui.label(format!("{} to {}", from.name(), to.name()));
+            }
+        });
+
+        match &self.diff {
+            Some(Ok(changes)) => {
+                ui.label(format!("{} changes", changes.len()));
+                egui::ScrollArea::vertical()
+                    .id_source("history_diff")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for change in changes.iter() {
+                            ui.label(RichText::new(change.to_string()).monospace());
+                        }
+                    });
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None => (),
+        }
+    }
+}
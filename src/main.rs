@@ -1,39 +1,60 @@
 use std::{
-    collections::HashMap,
-    fs,
     path::PathBuf,
+    process::ExitCode,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
     thread,
-    time::SystemTime,
 };
 
+use batch::BatchWindow;
 use chrono::{DateTime, Local};
+use clap::Parser;
+use cli::Cli;
+use history::HistoryWindow;
 use parking_lot::RwLock;
-use save::{Save, SaveInstance, SaveType, SelectedSave};
-use tools::SaveTool;
+use save::{
+    manage::{self, SteamIdSaves},
+    PlayerData, Save, SaveInstance, SaveType, SelectedSave,
+};
+use tools::{SaveTool, ToolPlayers, ToolRawJson};
 
+mod batch;
+mod cli;
+mod history;
+mod patch;
+mod repair;
 mod save;
+mod script;
 mod tools;
+mod validate;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        return cli::run(command, cli.save_dir, cli.backup_dir);
+    }
 
-fn main() {
     eframe::run_native(
         "Sons Of The Forest Save Tools",
         eframe::NativeOptions::default(),
-        Box::new(|cc| Box::new(SotfApp::new(cc))),
+        Box::new(move |cc| Box::new(SotfApp::new(cc, cli.save_dir, cli.backup_dir))),
     )
     .unwrap();
+
+    ExitCode::SUCCESS
 }
 
-/// An asynchronous resource. Effectively an Option<T> with an extra
-/// Loading variant.
+/// An asynchronous resource. Effectively an Option<T> with extra
+/// Loading and Failed variants.
 #[derive(Debug, Clone, Default)]
 enum AsyncOption<T> {
     #[default]
     None,
     Loading,
+    /// Loading failed, with why.
+    Failed(String),
     Some(T),
 }
 
@@ -46,22 +67,15 @@ impl<T> From<AsyncOption<T>> for Option<T> {
     }
 }
 
-/// All associated saves for a Steam ID.
-#[derive(Default, Clone)]
-struct SteamIdSaves {
-    /// The Steam ID corresponding to the child saves.
-    id: String,
-
-    /// The saves, categorized by save type (singleplayer/multiplayer).
-    saves: HashMap<SaveType, Vec<(String, SystemTime)>>,
-}
-
 /// The egui app.
 #[derive(Clone, Default)]
 struct SotfApp {
     /// The save directory.
     save_dir: PathBuf,
 
+    /// Where saves are backed up before they are written, if anywhere.
+    backup_dir: Option<PathBuf>,
+
     saves: Vec<SteamIdSaves>,
 
     /// The current save in-memory.
@@ -69,89 +83,72 @@ struct SotfApp {
 
     /// Whether or not we are currently saving changes.
     save_writing: Arc<AtomicBool>,
+
+    /// Text typed into a save's context menu.
+    manage_input: ManageInput,
+
+    /// A save awaiting confirmation before it is deleted.
+    pending_delete: Option<SelectedSave>,
+
+    /// The open save, changed on disk by a batch while it has unsaved changes, awaiting
+    /// confirmation before it is reloaded.
+    pending_reload: Option<SelectedSave>,
+
+    /// The result of the last save management operation.
+    manage_status: Option<String>,
+
+    batch: BatchWindow,
+
+    history: HistoryWindow,
+
+    /// Whether to show tools that edit the save's raw contents.
+    advanced: bool,
+}
+
+/// Text fields of the save management context menu.
+#[derive(Clone, Default)]
+struct ManageInput {
+    /// The save id to copy or rename to.
+    save_id: String,
+
+    /// The Steam ID to transfer to.
+    steam_id: String,
+}
+
+/// A save management operation requested from the save selector.
+enum ManageAction {
+    Clone(SelectedSave, Option<String>),
+    Rename(SelectedSave, String),
+    Delete(SelectedSave),
+    Convert(SelectedSave, SaveType, bool),
+    Transfer(SelectedSave, String, bool),
+    History(SelectedSave),
 }
 
 impl SotfApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let save_dir = PathBuf::from(std::env::var("UserProfile").unwrap())
-            .join("AppData\\LocalLow\\Endnight\\SonsOfTheForest\\Saves");
+    pub fn new(
+        _cc: &eframe::CreationContext<'_>,
+        save_dir: Option<PathBuf>,
+        backup_dir: Option<PathBuf>,
+    ) -> Self {
+        let save_dir = save_dir.or_else(manage::default_save_dir);
 
         assert!(
-            save_dir.is_dir(),
+            save_dir.as_ref().is_some_and(|d| d.is_dir()),
             "unable to find Sons Of The Forest save data"
         );
 
-        let steam_ids: Vec<String> = {
-            // get all entries in the saves folder
-            let mut save_dirs = fs::read_dir(save_dir.clone())
-                .unwrap()
-                .filter_map(Result::ok)
-                .filter(|entry| entry.file_type().unwrap().is_dir())
-                .map(|entry| {
-                    (
-                        entry.file_name(),
-                        entry.metadata().unwrap().modified().unwrap(),
-                    )
-                })
-                .collect::<Vec<_>>();
-
-            // sort by modified date
-            save_dirs.sort_by(|(_, a), (_, b)| b.cmp(a));
-
-            // map to a list of names
-            save_dirs
-                .into_iter()
-                .map(|(name, _)| name.into_string().unwrap())
-                .collect()
-        };
+        let save_dir = save_dir.unwrap();
+        let steam_id_saves = manage::scan_saves(&save_dir);
 
         assert!(
-            !steam_ids.is_empty(),
+            !steam_id_saves.is_empty(),
             "Sons Of The Forest game data exists, but there is no save data"
         );
 
-        let steam_id_saves = steam_ids
-            .into_iter()
-            .map(|id| {
-                let mut saves = HashMap::new();
-
-                for save_type in [
-                    SaveType::Singleplayer,
-                    SaveType::Multiplayer,
-                    SaveType::MultiplayerClient,
-                ] {
-                    let type_path = save_dir.join(&id).join(save_type.as_file());
-                    let mut type_saves = vec![];
-
-                    if !type_path.exists() {
-                        continue;
-                    }
-
-                    // grab all the save folders
-                    type_saves.extend(
-                        fs::read_dir(type_path)
-                            .unwrap()
-                            .filter_map(Result::ok)
-                            .filter(|e| e.file_type().unwrap().is_dir())
-                            .map(|e| {
-                                (
-                                    e.file_name().into_string().unwrap(),
-                                    e.metadata().unwrap().modified().unwrap(),
-                                )
-                            }),
-                    );
-
-                    type_saves.sort_by(|(_, a), (_, b)| b.cmp(a));
-
-                    saves.insert(save_type, type_saves);
-                }
-
-                SteamIdSaves { id, saves }
-            })
-            .collect::<Vec<_>>();
-
         Self {
             save_dir,
+            backup_dir,
             saves: steam_id_saves,
             ..Default::default()
         }
@@ -170,10 +167,19 @@ impl SotfApp {
             None => return,
         };
 
+        let save_dir = self.save_dir.clone();
+
         thread::spawn(move || {
             *mutex.write() = AsyncOption::Loading;
-            let save = Save::read(save_path).expect("failed to read save");
-            *mutex.write() = AsyncOption::Some(SaveInstance::new(selected, save));
+            *mutex.write() = match Save::read(save_path.clone()) {
+                Ok(mut save) => {
+                    if selected.1 == SaveType::Multiplayer {
+                        save.players = PlayerData::find(&save_dir, &save_path);
+                    }
+                    AsyncOption::Some(SaveInstance::new(selected, save))
+                }
+                Err(e) => AsyncOption::Failed(format!("Failed to read save {}: {}", selected.2, e)),
+            };
         });
     }
 
@@ -185,20 +191,69 @@ impl SotfApp {
             Some(s) => s,
             None => return,
         };
+        let backup_dir = self.backup_dir.clone();
 
         thread::spawn(move || {
             loading.store(true, Ordering::Relaxed);
 
-            if let AsyncOption::Some(ref instance) = *mutex.read() {
+            if let AsyncOption::Some(ref mut instance) = *mutex.write() {
                 instance
                     .save
-                    .write(save_path)
+                    .write(save_path, backup_dir.as_deref())
                     .expect("failed to write save");
+
+                instance.mark_saved();
             }
 
             loading.store(false, Ordering::Relaxed);
         });
     }
+
+    /// Run a save management operation and rescan the save directory.
+    fn manage_save(&mut self, action: ManageAction) {
+        let result = match &action {
+            ManageAction::Clone(selected, target) => manage::clone_save(
+                &self.save_dir,
+                selected,
+                target
+                    .as_deref()
+                    .map(|t| (selected.0.as_str(), selected.1, t)),
+            )
+            .map(|(_, _, name)| format!("Copied save {} to {}.", selected.2, name)),
+            ManageAction::Rename(selected, name) => {
+                manage::rename_save(&self.save_dir, selected, name)
+                    .map(|_| format!("Renamed save {} to {}.", selected.2, name))
+            }
+            ManageAction::Delete(selected) => manage::delete_save(&self.save_dir, selected)
+                .map(|_| format!("Deleted save {}.", selected.2)),
+            ManageAction::Convert(selected, target_type, dry_run) => {
+                manage::convert_save(&self.save_dir, selected, *target_type, *dry_run)
+                    .map(|report| report.to_string())
+            }
+            ManageAction::Transfer(selected, steam_id, dry_run) => {
+                manage::transfer_save(&self.save_dir, selected, steam_id, *dry_run)
+                    .map(|report| report.to_string())
+            }
+            ManageAction::History(selected) => {
+                self.history
+                    .show_save(&self.save_dir, self.backup_dir.clone(), selected.clone());
+                return;
+            }
+        };
+
+        // the loaded save no longer exists where it was read from
+        if let (Ok(_), ManageAction::Rename(selected, _) | ManageAction::Delete(selected)) =
+            (&result, &action)
+        {
+            let mut lock = self.save.write();
+            if matches!(*lock, AsyncOption::Some(ref instance) if instance.path == *selected) {
+                *lock = AsyncOption::None;
+            }
+        }
+
+        self.manage_status = Some(result.unwrap_or_else(|e| format!("Error: {}", e)));
+        self.saves = manage::scan_saves(&self.save_dir);
+    }
 }
 
 macro_rules! format_time {
@@ -212,6 +267,12 @@ macro_rules! format_time {
 
 impl eframe::App for SotfApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let mut action = None;
+        let mut open_batch = false;
+        let mut advanced = self.advanced;
+        let mut manage_input = std::mem::take(&mut self.manage_input);
+        let steam_ids = self.saves.iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+
         {
             let lock = self.save.read();
             let selected_save = match *lock {
@@ -221,7 +282,18 @@ impl eframe::App for SotfApp {
 
             egui::SidePanel::left("panel_save_selector").show(ctx, |ui| {
                 ui.heading("Save selector");
-                ui.label("Select a save below.");
+                ui.label("Select a save below. Right click a save to manage it.");
+
+                if ui.button("Batch operations").clicked() {
+                    open_batch = true;
+                }
+
+                ui.checkbox(&mut advanced, "Advanced mode")
+                    .on_hover_text("Show an editor for everything in the save.");
+
+                if let Some(status) = &self.manage_status {
+                    ui.label(status);
+                }
 
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
@@ -243,6 +315,20 @@ impl eframe::App for SotfApp {
                                                         egui::Button::new(name),
                                                     )
                                                     .on_hover_text(format_time!(time.to_owned()))
+                                                    .context_menu(|ui| {
+                                                        let selected = (
+                                                            id_saves.id.to_owned(),
+                                                            *save_type,
+                                                            name.to_owned(),
+                                                        );
+                                                        action = render_manage_menu(
+                                                            ui,
+                                                            selected,
+                                                            &mut manage_input,
+                                                            &steam_ids,
+                                                        )
+                                                        .or(action.take());
+                                                    })
                                                     .clicked()
                                                 {
                                                     self.read_save_async((
@@ -260,6 +346,101 @@ impl eframe::App for SotfApp {
             });
         }
 
+        self.manage_input = manage_input;
+        self.batch.open |= open_batch;
+        self.advanced = advanced;
+
+        // deleting from the selector asks for confirmation first
+        match action {
+            Some(ManageAction::Delete(selected)) => self.pending_delete = Some(selected),
+            Some(action) => self.manage_save(action),
+            None => (),
+        }
+
+        // reload the open save if a batch changed it on disk
+        let written = self
+            .batch
+            .show(ctx, &self.save_dir, self.backup_dir.as_deref(), &self.saves);
+        let reload = match *self.save.read() {
+            AsyncOption::Some(ref instance) if written.contains(&instance.path) => {
+                Some((instance.path.clone(), instance.has_unsaved_changes()))
+            }
+            _ => None,
+        };
+        match reload {
+            Some((selected, true)) => self.pending_reload = Some(selected),
+            Some((selected, false)) => self.read_save_async(selected),
+            None => (),
+        }
+
+        if let Some(selected) = self.pending_reload.clone() {
+            let mut confirmed = None;
+
+            egui::Window::new("Reload save")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "The batch changed save {}, which has unsaved changes. Reload it and \
+                         lose them, or keep editing? Saving then overwrites the batch's changes.",
+                        selected.2
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Reload").clicked() {
+                            confirmed = Some(true);
+                        }
+                        if ui.button("Keep editing").clicked() {
+                            confirmed = Some(false);
+                        }
+                    });
+                });
+
+            if let Some(confirmed) = confirmed {
+                self.pending_reload = None;
+                if confirmed {
+                    self.read_save_async(selected);
+                }
+            }
+        }
+
+        // and if it was rolled back
+        if let Some(selected) = self.history.show(ctx, &self.save_dir) {
+            let reload = matches!(*self.save.read(), AsyncOption::Some(ref instance) if instance.path == selected);
+            if reload {
+                self.read_save_async(selected);
+            }
+            self.saves = manage::scan_saves(&self.save_dir);
+        }
+
+        if let Some(selected) = self.pending_delete.clone() {
+            let mut confirmed = None;
+
+            egui::Window::new("Delete save")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "Permanently delete {} save {}?",
+                        selected.1, selected.2
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Delete").clicked() {
+                            confirmed = Some(true);
+                        }
+                        if ui.button("Cancel").clicked() {
+                            confirmed = Some(false);
+                        }
+                    });
+                });
+
+            if let Some(confirmed) = confirmed {
+                self.pending_delete = None;
+                if confirmed {
+                    self.manage_save(ManageAction::Delete(selected));
+                }
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             if self.save_writing.load(Ordering::Relaxed) {
                 ui.with_layout(
@@ -287,30 +468,184 @@ impl eframe::App for SotfApp {
                         egui::Ui::spinner,
                     );
                 }
+                AsyncOption::Failed(ref e) => {
+                    ui.with_layout(
+                        egui::Layout::centered_and_justified(egui::Direction::TopDown),
+                        |ui| ui.colored_label(ui.visuals().error_fg_color, e),
+                    );
+                }
                 AsyncOption::Some(ref mut save) => {
                     ui.heading("Save editor");
 
-                    egui::Grid::new("save_editor")
-                        .num_columns(2)
-                        .spacing([40.0, 4.0])
-                        .striped(true)
+                    for unparsed in save.save.unparsed.iter() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!(
+                                "{} couldn't be read, so it can't be edited and is kept as it \
+                                 is: {}",
+                                unparsed.file, unparsed.error
+                            ),
+                        );
+                    }
+
+                    egui::ScrollArea::vertical()
+                        .auto_shrink([false, false])
                         .show(ui, |ui| {
-                            ui.label("Kelvin");
-                            save.tools.kelvin.render(&mut save.save, ui);
-                            ui.end_row();
-
-                            ui.label("Virginia");
-                            save.tools.virginia.render(&mut save.save, ui);
-                            ui.end_row();
-
-                            ui.label("Save");
-                            if ui.button("Save changes").clicked() {
-                                self.write_save_async(save.path.clone());
-                            }
-                            ui.end_row();
+                            egui::Grid::new("save_editor")
+                                .num_columns(2)
+                                .spacing([40.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    let mut hidden = vec![];
+                                    if save.path.1 == SaveType::Singleplayer {
+                                        hidden.push(ToolPlayers::NAME);
+                                    }
+                                    if !self.advanced {
+                                        hidden.push(ToolRawJson::NAME);
+                                    }
+                                    save.tools.render(&mut save.save, ui, &hidden);
+
+                                    ui.label("Save");
+                                    if ui.button("Save changes").clicked() {
+                                        self.write_save_async(save.path.clone());
+                                    }
+                                    ui.end_row();
+                                });
                         });
                 }
             }
         });
     }
 }
+
+/// Render the context menu for managing a save in the save selector.
+fn render_manage_menu(
+    ui: &mut egui::Ui,
+    selected: SelectedSave,
+    input: &mut ManageInput,
+    steam_ids: &[String],
+) -> Option<ManageAction> {
+    let mut action = None;
+
+    if ui.button("Clone to new slot").clicked() {
+        action = Some(ManageAction::Clone(selected.clone(), None));
+        ui.close_menu();
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("Save id");
+        ui.text_edit_singleline(&mut input.save_id);
+    });
+
+    let valid_id = input.save_id.parse::<u64>().is_ok();
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(valid_id, egui::Button::new("Copy to slot"))
+            .clicked()
+        {
+            action = Some(ManageAction::Clone(
+                selected.clone(),
+                Some(input.save_id.clone()),
+            ));
+            ui.close_menu();
+        }
+
+        if ui
+            .add_enabled(valid_id, egui::Button::new("Rename"))
+            .clicked()
+        {
+            action = Some(ManageAction::Rename(
+                selected.clone(),
+                input.save_id.clone(),
+            ));
+            ui.close_menu();
+        }
+    });
+
+    let convert_to = match selected.1 {
+        SaveType::Singleplayer => Some(SaveType::Multiplayer),
+        SaveType::Multiplayer => Some(SaveType::Singleplayer),
+        SaveType::MultiplayerClient => None,
+    };
+
+    if let Some(target_type) = convert_to {
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button(format!("Convert to {}", target_type)).clicked() {
+                action = Some(ManageAction::Convert(selected.clone(), target_type, false));
+                ui.close_menu();
+            }
+
+            if ui
+                .button("Dry run")
+                .on_hover_text("Report what converting would do without changing anything.")
+                .clicked()
+            {
+                action = Some(ManageAction::Convert(selected.clone(), target_type, true));
+                ui.close_menu();
+            }
+        });
+    }
+
+    ui.separator();
+    ui.menu_button("Transfer to Steam ID", |ui| {
+        for steam_id in steam_ids.iter().filter(|id| **id != selected.0) {
+            if ui.button(steam_id).clicked() {
+                input.steam_id = steam_id.clone();
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Steam ID");
+            ui.text_edit_singleline(&mut input.steam_id);
+        });
+
+        let valid_id = input.steam_id.parse::<u64>().is_ok() && input.steam_id != selected.0;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(valid_id, egui::Button::new("Transfer"))
+                .clicked()
+            {
+                action = Some(ManageAction::Transfer(
+                    selected.clone(),
+                    input.steam_id.clone(),
+                    false,
+                ));
+                ui.close_menu();
+            }
+
+            if ui
+                .add_enabled(valid_id, egui::Button::new("Dry run"))
+                .clicked()
+            {
+                action = Some(ManageAction::Transfer(
+                    selected.clone(),
+                    input.steam_id.clone(),
+                    true,
+                ));
+                ui.close_menu();
+            }
+        });
+    });
+
+    ui.separator();
+    if ui
+        .button("History")
+        .on_hover_text(
+            "Compare the save to its backups and other slots of its world, or roll back to one.",
+        )
+        .clicked()
+    {
+        action = Some(ManageAction::History(selected.clone()));
+        ui.close_menu();
+    }
+
+    ui.separator();
+    if ui.button("Delete").clicked() {
+        action = Some(ManageAction::Delete(selected));
+        ui.close_menu();
+    }
+
+    action
+}
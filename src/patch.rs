@@ -0,0 +1,273 @@
+//! Declarative patch files: a list of edits to a save's document that can be reused across
+//! saves.
+//!
+//! A patch is JSON or TOML:
+//!
+//! ```toml
+//! description = "Bring Kelvin back"
+//! edits = [
+//!     "GameState.IsRobbyDead = false",
+//!     { path = "actor[type=9].Stats.Health", set = 100 },
+//! ]
+//! ```
+
+use std::{fs, path::Path as FsPath};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::save::{
+    json::{self, Change},
+    path::{parse_value, Path},
+    Save,
+};
+
+/// A single edit, either `"<path>=<value>"` or `{ path, set }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum EditSpec {
+    Short(String),
+    Full { path: String, set: Value },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PatchSpec {
+    #[serde(default)]
+    description: Option<String>,
+    edits: Vec<EditSpec>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Edit {
+    pub path: Path,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct Patch {
+    pub description: Option<String>,
+    pub edits: Vec<Edit>,
+}
+
+impl Patch {
+    /// Read a patch file, as TOML if it has a `.toml` extension and JSON otherwise.
+    pub fn read(path: &FsPath) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let spec: PatchSpec = if path.extension().is_some_and(|e| e == "toml") {
+            toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?
+        };
+
+        let edits = spec
+            .edits
+            .into_iter()
+            .map(|edit| match edit {
+                EditSpec::Short(s) => {
+                    let (path, value) = split_assignment(&s)
+                        .ok_or_else(|| format!("expected <path>=<value>, got {}", s))?;
+                    Ok(Edit {
+                        path: Path::parse(path)?,
+                        value: parse_value(value.trim()),
+                    })
+                }
+                EditSpec::Full { path, set } => Ok(Edit {
+                    path: Path::parse(&path)?,
+                    value: set,
+                }),
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            description: spec.description,
+            edits,
+        })
+    }
+
+    /// Apply the patch to a save. Every path must match at least one value of the same
+    /// type, and the result must still be a valid save, otherwise the save is unchanged.
+    pub fn apply(&self, save: &mut Save) -> Result<Vec<Change>, String> {
+        let mut document = save.to_document();
+        let mut changes = vec![];
+
+        for edit in self.edits.iter() {
+            let pointers = edit.path.resolve(&document);
+            if pointers.is_empty() {
+                return Err(format!("{} does not match anything", edit.path));
+            }
+
+            for pointer in pointers {
+                let value = document.pointer_mut(&pointer).unwrap();
+                if !same_type(value, &edit.value) {
+                    return Err(format!(
+                        "{}: cannot replace {} with {}",
+                        pointer, value, edit.value
+                    ));
+                }
+
+                if !json::same_value(value, &edit.value) {
+                    changes.push(Change {
+                        pointer,
+                        old: Some(std::mem::replace(value, edit.value.clone())),
+                        new: Some(edit.value.clone()),
+                    });
+                }
+            }
+        }
+
+        save.apply_document(&document)?;
+        Ok(changes)
+    }
+}
+
+/// Split `<path>=<value>` at the first `=` outside of a `[...]` selector.
+fn split_assignment(s: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '=' if depth == 0 => return Some((&s[..i], &s[i + 1..])),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Whether a value may replace another. Floats may be stored as the string `NaN`.
+fn same_type(old: &Value, new: &Value) -> bool {
+    let is_number = |v: &Value| v.is_number() || v.as_str() == Some("NaN");
+
+    match old {
+        Value::Null => true,
+        Value::Bool(_) => new.is_boolean(),
+        Value::Number(_) => is_number(new),
+        Value::String(s) if s == "NaN" => is_number(new),
+        Value::String(_) => new.is_string(),
+        Value::Array(_) => new.is_array(),
+        Value::Object(_) => new.is_object(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::ExitCode;
+
+    use super::*;
+    use crate::{
+        cli,
+        save::{
+            tests::{kelvin, save, Scratch, KELVIN},
+            ACTOR_STATE_ALIVE,
+        },
+    };
+
+    const REVIVE_KELVIN: &str = r#"
+description = "Revive Kelvin"
+edits = [
+    "GameState.IsRobbyDead = false",
+    { path = "actor[type=9].Stats.Health", set = 100 },
+]
+"#;
+
+    fn patch(edits: &[(&str, Value)]) -> Patch {
+        Patch {
+            description: None,
+            edits: edits
+                .iter()
+                .map(|(path, value)| Edit {
+                    path: Path::parse(path).unwrap(),
+                    value: value.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn dead_kelvin() -> Save {
+        let mut save = save(vec![kelvin(ACTOR_STATE_ALIVE, 0.0)], false);
+        (KELVIN.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+        save
+    }
+
+    #[test]
+    fn read_toml() {
+        let dir = Scratch::new("patch-read");
+        let file = dir.0.join("revive.toml");
+        fs::write(&file, REVIVE_KELVIN).unwrap();
+
+        let patch = Patch::read(&file).unwrap();
+
+        assert_eq!(patch.description.as_deref(), Some("Revive Kelvin"));
+        assert_eq!(patch.edits.len(), 2);
+        assert_eq!(patch.edits[0].path.to_string(), "GameState.IsRobbyDead");
+        assert_eq!(patch.edits[0].value, Value::Bool(false));
+        assert_eq!(patch.edits[1].value, Value::from(100));
+    }
+
+    #[test]
+    fn apply_reports_changes() {
+        let mut save = dead_kelvin();
+        let patch = patch(&[
+            ("GameState.IsRobbyDead", Value::Bool(false)),
+            ("actor[type=9].Stats.Health", Value::from(100)),
+            ("actor[type=9].State", Value::from(ACTOR_STATE_ALIVE)),
+        ]);
+
+        let changes = patch.apply(&mut save).unwrap();
+
+        // the state was already alive, so it isn't a change
+        assert_eq!(
+            changes.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            [
+                "/GameState/IsRobbyDead: true -> false",
+                "/SaveData/VailWorldSim/Actors/0/Stats/Health: 0.0 -> 100",
+            ]
+        );
+        assert!(!KELVIN.is_dead(&save));
+    }
+
+    #[test]
+    fn failed_edit_leaves_save_unchanged() {
+        let mut save = dead_kelvin();
+        let before = save.fingerprint();
+
+        for edits in [
+            // the first edit is fine, but nothing is left half applied
+            vec![
+                ("GameState.IsRobbyDead", Value::Bool(false)),
+                ("actor[type=10].Stats.Health", Value::from(100)),
+            ],
+            vec![("actor[type=9].Stats.Health", Value::from("full"))],
+        ] {
+            assert!(patch(&edits).apply(&mut save).is_err());
+            assert_eq!(save.fingerprint(), before);
+        }
+    }
+
+    #[test]
+    fn dry_run_writes_nothing() {
+        let dir = Scratch::new("patch-dry-run");
+        let file = dir.0.join("revive.toml");
+        let saves = [dir.0.join("save")];
+        let path = &saves[0];
+        fs::write(&file, REVIVE_KELVIN).unwrap();
+        fs::create_dir(path).unwrap();
+        dead_kelvin().write(path.clone(), None).unwrap();
+        let read = || Save::read(path.clone()).unwrap();
+
+        assert_eq!(
+            cli::apply_patch(&file, &saves, None, true),
+            ExitCode::SUCCESS
+        );
+        assert!(KELVIN.is_dead(&read()));
+
+        assert_eq!(
+            cli::apply_patch(&file, &saves, None, false),
+            ExitCode::SUCCESS
+        );
+        assert!(!KELVIN.is_dead(&read()));
+    }
+}
@@ -0,0 +1,353 @@
+//! Automatic fixes for common save corruption. Every fix is optional and reported.
+
+use std::{fmt::Display, fs, io, path::Path, str::FromStr};
+
+use serde_json::Value;
+
+use crate::save::{manage, Resurrection, Save, ACTOR_STATE_DEAD, NPCS};
+
+/// Health given to living actors other than the companions whose health is NaN.
+const DEFAULT_HEALTH: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    NanStats,
+    MissingCompanions,
+    ConflictingFlags,
+    TruncatedFiles,
+}
+
+impl Repair {
+    pub const ALL: [Repair; 4] = [
+        Self::NanStats,
+        Self::MissingCompanions,
+        Self::ConflictingFlags,
+        Self::TruncatedFiles,
+    ];
+
+    /// The repairs made unless others are picked. Resolving conflicting flags changes
+    /// whether a companion is dead, so it is only made when asked for.
+    pub const DEFAULT: [Repair; 3] = [
+        Self::NanStats,
+        Self::MissingCompanions,
+        Self::TruncatedFiles,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NanStats => {
+                "Give living actors other than dead companions full health if it is NaN. \
+                Other NaN stats are reported, as there is no safe value for them."
+            }
+            Self::MissingCompanions => {
+                "Re-add Kelvin or Virginia near the player if their actor is missing and \
+                they aren't dead."
+            }
+            Self::ConflictingFlags => {
+                "Mark Kelvin and Virginia dead, flag and actor, if either says they are dead."
+            }
+            Self::TruncatedFiles => "Restore files that aren't valid JSON from the latest backup.",
+        }
+    }
+}
+
+impl Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NanStats => "nan-stats",
+            Self::MissingCompanions => "missing-companions",
+            Self::ConflictingFlags => "conflicting-flags",
+            Self::TruncatedFiles => "truncated-files",
+        })
+    }
+}
+
+impl FromStr for Repair {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|r| r.to_string() == s)
+            .ok_or_else(|| format!("unknown repair: {}", s))
+    }
+}
+
+/// A fix made, or one that was needed but couldn't be made.
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub repair: Repair,
+    pub applied: bool,
+    pub message: String,
+}
+
+impl Fix {
+    fn applied(repair: Repair, message: String) -> Self {
+        Self {
+            repair,
+            applied: true,
+            message,
+        }
+    }
+
+    fn skipped(repair: Repair, message: String) -> Self {
+        Self {
+            repair,
+            applied: false,
+            message,
+        }
+    }
+}
+
+impl Display for Fix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.applied { "fixed  " } else { "skipped" };
+        write!(f, "{} [{}] {}", status, self.repair, self.message)
+    }
+}
+
+/// Apply the selected repairs to a loaded save. Truncated files can't be loaded, so they
+/// are recovered with [`recover_files`] instead.
+pub fn repair_save(save: &mut Save, repairs: &[Repair]) -> Vec<Fix> {
+    let mut fixes = vec![];
+
+    if repairs.contains(&Repair::NanStats) {
+        repair_nan_stats(save, &mut fixes);
+    }
+
+    if repairs.contains(&Repair::MissingCompanions) {
+        repair_missing_companions(save, &mut fixes);
+    }
+
+    if repairs.contains(&Repair::ConflictingFlags) {
+        repair_conflicting_flags(save, &mut fixes);
+    }
+
+    fixes
+}
+
+fn repair_nan_stats(save: &mut Save, fixes: &mut Vec<Fix>) {
+    // NaN health counts as dead for the companions, so only resurrecting brings them back
+    let dead_companions = NPCS
+        .iter()
+        .filter(|npc| npc.is_dead(save))
+        .map(|npc| npc.type_id)
+        .collect::<Vec<_>>();
+
+    for (i, actor) in save
+        .save_data
+        .data
+        .vail_world_sim
+        .actors
+        .iter_mut()
+        .enumerate()
+    {
+        let Some(stats) = actor.stats.as_mut() else {
+            continue;
+        };
+
+        if actor.state == ACTOR_STATE_DEAD || dead_companions.contains(&actor.type_id) {
+            continue;
+        }
+
+        if stats.health.is_nan() {
+            stats.health = DEFAULT_HEALTH;
+
+            fixes.push(Fix::applied(
+                Repair::NanStats,
+                format!(
+                    "set actor {} (type {}) health to {}",
+                    i, actor.type_id, stats.health
+                ),
+            ));
+        }
+
+        // hunger, energy and the like have no value that is safe for every actor
+        for (key, value) in stats.other.iter() {
+            if value.as_str() == Some("NaN") {
+                fixes.push(Fix::skipped(
+                    Repair::NanStats,
+                    format!("actor {} (type {}) {} is NaN", i, actor.type_id, key),
+                ));
+            }
+        }
+    }
+}
+
+fn repair_missing_companions(save: &mut Save, fixes: &mut Vec<Fix>) {
+    for npc in NPCS {
+        if save.actor(npc.type_id).is_some() || npc.is_dead(save) {
+            continue;
+        }
+
+        // a missing NPC that isn't dead is re-added the same way as resurrecting them
+        fixes.push(match npc.resurrect(save) {
+            Resurrection::Added(position) => Fix::applied(
+                Repair::MissingCompanions,
+                format!("re-added {} at {}", npc.name, position),
+            ),
+            _ => Fix::skipped(
+                Repair::MissingCompanions,
+                format!(
+                    "{} is missing, but the player's position is unknown",
+                    npc.name
+                ),
+            ),
+        });
+    }
+}
+
+fn repair_conflicting_flags(save: &mut Save, fixes: &mut Vec<Fix>) {
+    for npc in NPCS {
+        let Some(actor_dead) = save.actor(npc.type_id).map(|a| a.state == ACTOR_STATE_DEAD) else {
+            continue;
+        };
+        if (npc.is_flagged_dead)(&save.game_state.data.game_state) == actor_dead {
+            continue;
+        }
+
+        // they disagree, so one of them says dead, which wins
+        (npc.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+        if let Some(actor) = save.actor_mut(npc.type_id) {
+            actor.state = ACTOR_STATE_DEAD;
+        }
+
+        fixes.push(Fix::applied(
+            Repair::ConflictingFlags,
+            format!(
+                "marked {} dead, as their {} said so",
+                npc.name,
+                if actor_dead { "actor" } else { "dead flag" }
+            ),
+        ));
+    }
+}
+
+/// Restore every file in a save folder that isn't valid JSON from the newest backup in a
+/// backup directory that has a valid copy.
+pub fn recover_files(
+    path: &Path,
+    backup_dir: Option<&Path>,
+    dry_run: bool,
+) -> io::Result<Vec<Fix>> {
+    let mut fixes = vec![];
+    let backups = backup_dir.map_or_else(Vec::new, |dir| manage::backups(dir, path));
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file = entry.path();
+        if !entry.file_type()?.is_file() || file.extension().map_or(true, |e| e != "json") {
+            continue;
+        }
+
+        if is_valid_json(&file) {
+            continue;
+        }
+
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let backup = backups
+            .iter()
+            .map(|backup| backup.join(entry.file_name()))
+            .find(|backup| is_valid_json(backup));
+
+        fixes.push(match backup {
+            Some(backup) => {
+                if !dry_run {
+                    fs::copy(&backup, &file)?;
+                }
+
+                Fix::applied(
+                    Repair::TruncatedFiles,
+                    format!("restored {} from {}", name, backup.display()),
+                )
+            }
+            None => Fix::skipped(
+                Repair::TruncatedFiles,
+                format!(
+                    "{} is not valid JSON and has no valid backup in --backup-dir",
+                    name
+                ),
+            ),
+        });
+    }
+
+    Ok(fixes)
+}
+
+fn is_valid_json(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|text| serde_json::from_str::<Value>(&text).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::{
+        tests::{kelvin, save, KELVIN},
+        ACTOR_STATE_ALIVE,
+    };
+
+    #[test]
+    fn nan_stats_leave_dead_by_flag_dead() {
+        let mut save = save(vec![kelvin(ACTOR_STATE_ALIVE, f32::NAN)], false);
+        (KELVIN.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+
+        repair_save(&mut save, &Repair::DEFAULT);
+
+        assert!(KELVIN.is_dead(&save));
+        assert!(save
+            .actor(KELVIN.type_id)
+            .unwrap()
+            .stats
+            .as_ref()
+            .unwrap()
+            .health
+            .is_nan());
+    }
+
+    #[test]
+    fn nan_stats_leave_dead_by_nan_health_dead() {
+        let mut save = save(vec![kelvin(ACTOR_STATE_ALIVE, f32::NAN)], false);
+
+        repair_save(&mut save, &Repair::DEFAULT);
+
+        assert!(KELVIN.is_dead(&save));
+    }
+
+    #[test]
+    fn conflicting_flags_resolve_toward_dead_by_flag() {
+        let mut save = save(vec![kelvin(ACTOR_STATE_ALIVE, 100.0)], false);
+        (KELVIN.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+
+        let fixes = repair_save(&mut save, &Repair::ALL);
+
+        assert!(fixes
+            .iter()
+            .any(|fix| fix.repair == Repair::ConflictingFlags));
+        assert!((KELVIN.is_flagged_dead)(&save.game_state.data.game_state));
+        assert_eq!(save.actor(KELVIN.type_id).unwrap().state, ACTOR_STATE_DEAD);
+    }
+
+    #[test]
+    fn conflicting_flags_resolve_toward_dead_by_nan_health() {
+        let mut save = save(vec![kelvin(ACTOR_STATE_DEAD, f32::NAN)], false);
+
+        repair_save(&mut save, &Repair::ALL);
+
+        assert!(KELVIN.is_dead(&save));
+        assert!((KELVIN.is_flagged_dead)(&save.game_state.data.game_state));
+    }
+
+    #[test]
+    fn conflicting_flags_are_opt_in() {
+        let mut save = save(vec![kelvin(ACTOR_STATE_ALIVE, 100.0)], false);
+        (KELVIN.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+
+        let fixes = repair_save(&mut save, &Repair::DEFAULT);
+
+        assert!(!fixes
+            .iter()
+            .any(|fix| fix.repair == Repair::ConflictingFlags));
+        assert_eq!(save.actor(KELVIN.type_id).unwrap().state, ACTOR_STATE_ALIVE);
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+use super::Vector3;
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct Constructions {
+    #[serde_as(as = "JsonString")]
+    pub constructions: ConstructionsInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ConstructionsInner {
+    pub structures: Vec<Structure>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// A player-built structure made up of individual elements (logs, planks, etc.).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct Structure {
+    pub id: u32,
+    pub position: Vector3,
+    pub elements: Vec<StructureElement>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct StructureElement {
+    pub type_id: u32,
+    pub position: Vector3,
+
+    #[serde(
+        with = "super::f32_nan_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub damage: Option<f32>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ScrewStructureInstances {
+    #[serde_as(as = "JsonString")]
+    pub screw_structure_instances: ScrewStructureInstancesInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ScrewStructureInstancesInner {
+    pub structures: Vec<ScrewStructure>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// A blueprint structure placed from the guide book (shelters, racks, etc.).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ScrewStructure {
+    pub id: u32,
+    pub pos: Vector3,
+
+    #[serde(
+        with = "super::f32_nan_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub damage: Option<f32>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
@@ -0,0 +1,140 @@
+//! A save as a single JSON document, with the nested JSON strings of every file expanded.
+//!
+//! Each file is a root of the document, named after the file without its `SaveData.json`
+//! suffix (`SaveData.json` itself is `SaveData`). A root holds the file's `Data`, unwrapped
+//! if it only has a key named after the root, so `GameState.IsRobbyDead` is the flag in
+//! `GameStateSaveData.json`.
+
+use serde_json::{Map, Value};
+
+use super::{json, with_save_files, Save};
+
+/// The name of a file's root in the document.
+pub fn root_name(file: &str) -> &str {
+    match file.strip_suffix("SaveData.json") {
+        Some("") | None => file.strip_suffix(".json").unwrap_or(file),
+        Some(name) => name,
+    }
+}
+
+/// A file's data expanded into a document root.
+pub struct ExpandedRoot {
+    pub value: Value,
+    pointers: Vec<String>,
+    unwrapped: bool,
+}
+
+impl ExpandedRoot {
+    /// Expand the data of a file.
+    pub fn from_file(file: &Value, root: &str) -> Self {
+        let mut data = file.get("Data").cloned().unwrap_or(Value::Null);
+        let mut pointers = json::expand(&mut data);
+
+        let unwrapped =
+            matches!(&data, Value::Object(map) if map.len() == 1 && map.contains_key(root));
+        if unwrapped {
+            data = data[root].take();
+
+            let prefix = format!("/{}", json::escape(root));
+            pointers = pointers
+                .into_iter()
+                .filter_map(|p| p.strip_prefix(&prefix).map(str::to_owned))
+                .collect();
+        }
+
+        Self {
+            value: data,
+            pointers,
+            unwrapped,
+        }
+    }
+
+    /// Collapse a (possibly edited) root back into a file, using this root's nesting.
+    pub fn to_file(&self, root: &str, value: Value, mut file: Value) -> Value {
+        let mut data = value;
+        json::collapse(&mut data, &self.pointers);
+
+        if self.unwrapped {
+            let mut map = Map::new();
+            map.insert(root.to_owned(), data);
+            data = Value::Object(map);
+        }
+
+        file["Data"] = data;
+        file
+    }
+}
+
+impl Save {
+    /// The save as a single document of every loaded file.
+    pub fn to_document(&self) -> Value {
+        let mut document = Map::new();
+
+        macro_rules! add_file {
+            (required $name:ident => $file:literal) => {
+                let file = serde_json::to_value(&self.$name).unwrap_or_default();
+                let root = root_name($file);
+                document.insert(root.to_owned(), ExpandedRoot::from_file(&file, root).value);
+            };
+            (optional $name:ident => $file:literal) => {
+                if let Some(ref $name) = self.$name {
+                    let file = serde_json::to_value($name).unwrap_or_default();
+                    let root = root_name($file);
+                    document.insert(root.to_owned(), ExpandedRoot::from_file(&file, root).value);
+                }
+            };
+        }
+
+        macro_rules! add_files {
+            ($($kind:ident $name:ident : $type:ty => $file:literal,)*) => {
+                $(add_file!($kind $name => $file);)*
+            };
+        }
+
+        with_save_files!(add_files);
+
+        Value::Object(document)
+    }
+
+    /// Replace the save's contents with an edited document. Every changed file must still
+    /// match the typed model, otherwise nothing is changed.
+    pub fn apply_document(&mut self, document: &Value) -> Result<(), String> {
+        let mut save = self.clone();
+
+        macro_rules! apply_file {
+            ($field:expr, $file:literal) => {
+                let file = serde_json::to_value(&$field).map_err(|e| e.to_string())?;
+                let root = root_name($file);
+                let expanded = ExpandedRoot::from_file(&file, root);
+
+                if let Some(value) = document.get(root).filter(|v| **v != expanded.value) {
+                    let file = expanded.to_file(root, value.clone(), file);
+                    $field =
+                        serde_json::from_value(file).map_err(|e| format!("{}: {}", root, e))?;
+                }
+            };
+        }
+
+        macro_rules! apply_files_kind {
+            (required $name:ident => $file:literal) => {
+                apply_file!(save.$name, $file);
+            };
+            (optional $name:ident => $file:literal) => {
+                if let Some(ref mut $name) = save.$name {
+                    apply_file!(*$name, $file);
+                }
+            };
+        }
+
+        macro_rules! apply_files {
+            ($($kind:ident $name:ident : $type:ty => $file:literal,)*) => {
+                $(apply_files_kind!($kind $name => $file);)*
+            };
+        }
+
+        with_save_files!(apply_files);
+
+        *self = save;
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+//! Export a save folder as readable JSON, with every nested JSON string expanded, and import
+//! it back into the game's format.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+
+use super::{json, with_save_files, GenericData};
+
+/// A file of a save folder. JSON files are converted, anything else is copied as is.
+enum Contents {
+    Json(String),
+    Other(Vec<u8>),
+}
+
+/// Key of an exported file listing the JSON pointers of the strings that were expanded.
+const NESTED_KEY: &str = "$nested";
+
+fn invalid_data(e: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// Expand a game file into pretty printed JSON.
+fn export_file(text: &str) -> serde_json::Result<String> {
+    let mut value: Value = serde_json::from_str(text)?;
+    let pointers = json::expand(&mut value);
+
+    let value = match value {
+        Value::Object(map) => {
+            let mut exported = Map::new();
+            exported.insert(NESTED_KEY.to_owned(), pointers.into());
+            exported.extend(map);
+            Value::Object(exported)
+        }
+        value => value,
+    };
+
+    serde_json::to_string_pretty(&value)
+}
+
+/// Collapse an exported file back into the game's format.
+fn import_file(text: &str) -> serde_json::Result<String> {
+    let mut value: Value = serde_json::from_str(text)?;
+
+    let pointers: Vec<String> = match &mut value {
+        Value::Object(map) if map.contains_key(NESTED_KEY) => {
+            let nested = map[NESTED_KEY].clone();
+            // rebuilt rather than removed, which would reorder the keys
+            *map = std::mem::take(map)
+                .into_iter()
+                .filter(|(key, _)| key != NESTED_KEY)
+                .collect();
+            serde_json::from_value(nested)?
+        }
+        _ => vec![],
+    };
+
+    json::collapse(&mut value, &pointers);
+    serde_json::to_string(&value)
+}
+
+/// Export every file of a save folder into a directory, including those in subfolders such
+/// as the other players' data of a host save. JSON files are expanded, anything else is
+/// copied as is. Fails without writing anything if a file would not import back
+/// identically. Returns the number of files exported.
+pub fn export_save(save: &Path, to: &Path) -> io::Result<usize> {
+    let files = convert_files(save, |name, text| {
+        let exported = export_file(text).map_err(|e| format!("{}: {}", name.display(), e))?;
+        if import_file(&exported).ok().as_deref() != Some(text) {
+            return Err(format!(
+                "{} would not import back identically",
+                name.display()
+            ));
+        }
+        Ok(exported)
+    })?;
+
+    write_files(to, &files)
+}
+
+/// Import a directory exported by [`export_save`] into a save folder. Every file is checked
+/// against the save model before anything is written. Returns the number of files imported.
+pub fn import_save(from: &Path, save: &Path) -> io::Result<usize> {
+    let files = convert_files(from, |name, text| {
+        import_file(text).map_err(|e| format!("{}: {}", name.display(), e))
+    })?;
+
+    macro_rules! check_file {
+        ($type:ty => $file:literal) => {
+            // the other players' folders hold the same files as the save itself
+            for (name, contents) in files.iter().filter(|(name, _)| name.ends_with($file)) {
+                if let Contents::Json(text) = contents {
+                    serde_json::from_str::<GenericData<$type>>(text)
+                        .map_err(|e| invalid_data(format!("{}: {}", name.display(), e)))?;
+                }
+            }
+        };
+    }
+
+    macro_rules! check_files {
+        ($($kind:ident $name:ident : $type:ty => $file:literal,)*) => {
+            $(check_file!($type => $file);)*
+        };
+    }
+
+    with_save_files!(check_files);
+
+    write_files(save, &files)
+}
+
+/// Read every file in a directory and its subdirectories, converting the text of JSON files.
+/// Files are named by their path relative to `dir`.
+fn convert_files(
+    dir: &Path,
+    convert: impl Fn(&Path, &str) -> Result<String, String>,
+) -> io::Result<Vec<(PathBuf, Contents)>> {
+    let mut files = vec![];
+    let mut dirs = vec![PathBuf::new()];
+
+    while let Some(sub_dir) = dirs.pop() {
+        for entry in fs::read_dir(dir.join(&sub_dir))? {
+            let entry = entry?;
+            let name = sub_dir.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                dirs.push(name);
+                continue;
+            } else if !file_type.is_file() {
+                return Err(invalid_data(format!(
+                    "{} is neither a file nor a folder",
+                    name.display()
+                )));
+            }
+
+            let path = entry.path();
+            let contents = if path.extension().is_some_and(|e| e == "json") {
+                let text = fs::read_to_string(&path)?;
+                Contents::Json(convert(&name, &text).map_err(invalid_data)?)
+            } else {
+                Contents::Other(fs::read(&path)?)
+            };
+
+            files.push((name, contents));
+        }
+    }
+
+    Ok(files)
+}
+
+fn write_files(dir: &Path, files: &[(PathBuf, Contents)]) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+
+    for (name, contents) in files.iter() {
+        if let Some(parent) = name.parent() {
+            fs::create_dir_all(dir.join(parent))?;
+        }
+        match contents {
+            Contents::Json(text) => fs::write(dir.join(name), text)?,
+            Contents::Other(bytes) => fs::write(dir.join(name), bytes)?,
+        }
+    }
+
+    Ok(files.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::tests::Scratch;
+
+    /// A game file as the game writes it, with its data nested as a JSON string.
+    const GAME_STATE: &str = r#"{"Version":"0.0.0","Data":{"GameState":"{\"GameType\":\"Normal\",\"GameDays\":12,\"GameTime\":0.1,\"Weather\":{\"Rain\":0.635,\"Wind\":115.0}}"}}"#;
+    const PLAYER_STATE: &str = r#"{"Version":"0.0.0","Data":{"PlayerState":"{\"_entries\":[{\"Name\":\"player.position\",\"FloatArrayValue\":[-251.358398,115.0,-0.0],\"SaveType\":7}]}"}}"#;
+
+    const PLAYER_DIR: &str = "Players/76561198000000002";
+
+    #[test]
+    fn game_floats_export_identically() {
+        for text in [GAME_STATE, PLAYER_STATE] {
+            let exported = export_file(text).unwrap();
+            assert!(exported.contains("\"$nested\""));
+            assert_eq!(import_file(&exported).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn exponent_floats_are_refused() {
+        let save = Scratch::new("exponent-save");
+        let to = Scratch::new("exponent-export");
+        let text = GAME_STATE.replace("0.1", "1E-05");
+        fs::write(save.0.join("GameStateSaveData.json"), text).unwrap();
+
+        let error = export_save(&save.0, &to.0).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("would not import back identically"));
+        assert_eq!(fs::read_dir(&to.0).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn round_trip_keeps_player_folders() {
+        let save = Scratch::new("round-trip-save");
+        let exported = Scratch::new("round-trip-export");
+        let imported = Scratch::new("round-trip-import");
+        let players = save.0.join(PLAYER_DIR);
+        fs::create_dir_all(&players).unwrap();
+        fs::write(save.0.join("GameStateSaveData.json"), GAME_STATE).unwrap();
+        fs::write(players.join("PlayerStateSaveData.json"), PLAYER_STATE).unwrap();
+        fs::write(save.0.join("Thumbnail.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        assert_eq!(export_save(&save.0, &exported.0).unwrap(), 3);
+        let player =
+            fs::read_to_string(exported.0.join(PLAYER_DIR).join("PlayerStateSaveData.json"))
+                .unwrap();
+        assert!(player.contains("\"player.position\""));
+
+        assert_eq!(import_save(&exported.0, &imported.0).unwrap(), 3);
+        for name in [
+            Path::new("GameStateSaveData.json"),
+            Path::new("Thumbnail.png"),
+            &Path::new(PLAYER_DIR).join("PlayerStateSaveData.json"),
+        ] {
+            assert_eq!(
+                fs::read(imported.0.join(name)).unwrap(),
+                fs::read(save.0.join(name)).unwrap(),
+                "{}",
+                name.display()
+            );
+        }
+    }
+}
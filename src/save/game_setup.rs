@@ -0,0 +1,123 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct GameSetup {
+    #[serde_as(as = "JsonString")]
+    pub game_setup: GameSetupInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSetupInner {
+    #[serde(rename = "_settings")]
+    pub settings: Vec<GameSetupSetting>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// A single named game setup setting, e.g. `Mode`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct GameSetupSetting {
+    pub name: String,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+impl GameSetupSetting {
+    pub fn new_string(name: &str, value: &str) -> Self {
+        let mut setting = Self {
+            name: name.to_owned(),
+            ..Default::default()
+        };
+        setting.set_string_value(value);
+        setting
+    }
+
+    pub fn string_value(&self) -> Option<&str> {
+        self.other.get("StringValue")?.as_str()
+    }
+
+    pub fn set_string_value(&mut self, value: &str) {
+        self.other.insert("StringValue".into(), Value::from(value));
+    }
+}
+
+/// The setting holding the season a custom game starts in.
+const STARTING_SEASON_SETTING: &str = "StartingSeason";
+
+impl GameSetupInner {
+    pub fn setting(&self, name: &str) -> Option<&GameSetupSetting> {
+        self.settings.iter().find(|s| s.name == name)
+    }
+
+    /// Set a string setting, adding it if the save doesn't have it yet.
+    pub fn set_string_setting(&mut self, name: &str, value: &str) {
+        match self.settings.iter_mut().find(|s| s.name == name) {
+            Some(setting) => setting.set_string_value(value),
+            None => self
+                .settings
+                .push(GameSetupSetting::new_string(name, value)),
+        }
+    }
+
+    /// The game mode, e.g. `Normal`, `Hard` or `Custom`.
+    pub fn mode(&self) -> Option<&str> {
+        self.setting("Mode")?.string_value()
+    }
+
+    /// Set the season the game starts in. This only has an effect in custom games.
+    pub fn set_starting_season(&mut self, season: Season) {
+        self.set_string_setting(STARTING_SEASON_SETTING, &season.to_string());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Season::Spring => "Spring",
+            Season::Summer => "Summer",
+            Season::Autumn => "Autumn",
+            Season::Winter => "Winter",
+        })
+    }
+}
+
+impl FromStr for Season {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|season| season.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown season: {}", s))
+    }
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+use super::ItemBlock;
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerInventory {
+    #[serde_as(as = "JsonString")]
+    pub player_inventory: PlayerInventoryInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerInventoryInner {
+    pub item_instance_manager_data: ItemInstanceManagerData,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ItemInstanceManagerData {
+    pub item_blocks: Vec<ItemBlock>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
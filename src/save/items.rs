@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A stack of items of one type, as stored in inventories and storage.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ItemBlock {
+    pub item_id: u32,
+    pub total_count: u32,
+    pub unique_items: Vec<Value>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+impl ItemBlock {
+    pub fn new(item_id: u32, total_count: u32) -> Self {
+        Self {
+            item_id,
+            total_count,
+            ..Default::default()
+        }
+    }
+}
+
+/// What an item is used for, which decides where it can be equipped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemKind {
+    Resource,
+    Ammo,
+    Weapon,
+    Tool,
+    Armour {
+        /// The armour points of a fresh piece.
+        durability: f32,
+    },
+    Clothing,
+}
+
+/// Static information about an item type.
+#[derive(Debug, Clone, Copy)]
+pub struct ItemInfo {
+    pub id: u32,
+    pub name: &'static str,
+    pub kind: ItemKind,
+
+    /// How many of this item fit in a single storage slot.
+    pub max_stack: u32,
+}
+
+macro_rules! items {
+    ($($id:literal => $name:literal, $kind:expr, $max_stack:literal;)*) => {
+        /// Known items. This is not exhaustive; unknown item ids are still accepted.
+        pub const ITEMS: &[ItemInfo] = &[$(
+            ItemInfo {
+                id: $id,
+                name: $name,
+                kind: {
+                    use ItemKind::*;
+                    $kind
+                },
+                max_stack: $max_stack,
+            },
+        )*];
+    };
+}
+
+items! {
+    78 => "Log", Resource, 12;
+    392 => "Stick", Resource, 50;
+    640 => "Stone", Resource, 30;
+    405 => "Bone", Resource, 50;
+    484 => "Leaf", Resource, 50;
+    403 => "Rope", Resource, 20;
+    415 => "Cloth", Resource, 20;
+    419 => "Duct tape", Resource, 20;
+    527 => "Battery", Resource, 20;
+    496 => "Cash", Resource, 100;
+    502 => "Coins", Resource, 100;
+    362 => "9mm ammo", Ammo, 100;
+    364 => "Buckshot ammo", Ammo, 50;
+    363 => "Slug ammo", Ammo, 50;
+    373 => "Crafted arrow", Ammo, 50;
+    355 => "Pistol", Weapon, 1;
+    358 => "Shotgun", Weapon, 1;
+    359 => "Machete", Weapon, 1;
+    356 => "Modern axe", Tool, 1;
+    379 => "Tactical axe", Tool, 1;
+    473 => "Leaf armour", Armour { durability: 10.0 }, 10;
+    494 => "Bone armour", Armour { durability: 20.0 }, 10;
+    519 => "Hide armour", Armour { durability: 25.0 }, 10;
+    554 => "Tech armour", Armour { durability: 40.0 }, 10;
+    572 => "Golden armour", Armour { durability: 50.0 }, 10;
+    593 => "Creepy armour", Armour { durability: 30.0 }, 10;
+    458 => "Hoodie", Clothing, 1;
+    505 => "Winter jacket", Clothing, 1;
+    506 => "Hide outfit", Clothing, 1;
+    507 => "Tech suit", Clothing, 1;
+}
+
+/// Look up a known item by id.
+pub fn item_info(id: u32) -> Option<&'static ItemInfo> {
+    ITEMS.iter().find(|i| i.id == id)
+}
+
+/// A display name for an item id, falling back to the id itself.
+pub fn item_name(id: u32) -> String {
+    item_info(id).map_or_else(|| format!("Item #{}", id), |i| i.name.to_owned())
+}
@@ -0,0 +1,260 @@
+//! Helpers for the game's JSON files, which nest whole JSON documents inside strings.
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufReader},
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// Read and parse a JSON file.
+pub fn read_file<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    serde_json::from_reader(BufReader::new(File::open(path)?))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Read and parse a JSON file, if it exists.
+pub fn read_optional_file<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    if path.is_file() {
+        read_file(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Write a value to a JSON file.
+pub fn write_file<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    serde_json::to_writer(File::create(path)?, value)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Expand every string holding a JSON object or array into the value itself, recursively.
+/// Returns the JSON pointers of the expanded strings, outermost first, so they can be
+/// collapsed back with [`collapse`].
+pub fn expand(value: &mut Value) -> Vec<String> {
+    let mut pointers = vec![];
+    expand_at(value, String::new(), &mut pointers);
+    pointers
+}
+
+fn expand_at(value: &mut Value, pointer: String, pointers: &mut Vec<String>) {
+    if let Value::String(s) = value {
+        let trimmed = s.trim_start();
+        if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+            return;
+        }
+
+        match serde_json::from_str::<Value>(s) {
+            Ok(inner) => {
+                *value = inner;
+                pointers.push(pointer.clone());
+            }
+            Err(_) => return,
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                expand_at(child, format!("{}/{}", pointer, escape(key)), pointers);
+            }
+        }
+        Value::Array(array) => {
+            for (i, child) in array.iter_mut().enumerate() {
+                expand_at(child, format!("{}/{}", pointer, i), pointers);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Re-encode the values at the given pointers into JSON strings, undoing [`expand`].
+pub fn collapse(value: &mut Value, pointers: &[String]) {
+    // innermost first, so outer strings contain the collapsed inner ones
+    for pointer in pointers.iter().rev() {
+        if let Some(inner) = value.pointer_mut(pointer) {
+            *inner = Value::String(inner.to_string());
+        }
+    }
+}
+
+/// A value that differs between two documents. A missing value is `None`.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub pointer: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "(none)".to_owned(),
+        };
+
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.pointer,
+            show(&self.old),
+            show(&self.new)
+        )
+    }
+}
+
+/// Every scalar, or whole object or array, that differs between two documents.
+pub fn diff(old: &Value, new: &Value) -> Vec<Change> {
+    let mut changes = vec![];
+    diff_at(Some(old), Some(new), String::new(), &mut changes);
+    changes
+}
+
+fn diff_at(old: Option<&Value>, new: Option<&Value>, pointer: String, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Some(Value::Object(old)), Some(Value::Object(new))) => {
+            let keys = old
+                .keys()
+                .chain(new.keys().filter(|k| !old.contains_key(*k)));
+            for key in keys {
+                let pointer = format!("{}/{}", pointer, escape(key));
+                diff_at(old.get(key), new.get(key), pointer, changes);
+            }
+        }
+        (Some(Value::Array(old)), Some(Value::Array(new))) => {
+            for i in 0..old.len().max(new.len()) {
+                let pointer = format!("{}/{}", pointer, i);
+                diff_at(old.get(i), new.get(i), pointer, changes);
+            }
+        }
+        (Some(old), Some(new)) if same_value(old, new) => (),
+        (old, new) => changes.push(Change {
+            pointer,
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+    }
+}
+
+/// Whether two values are equal, comparing numbers by value so `120` equals `120.0`.
+pub fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Escape a key for use in a JSON pointer.
+pub fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Replace every string or integer equal to `from` with `to`, returning how many values were
+/// replaced.
+pub fn replace_scalar(value: &mut Value, from: &str, to: &str) -> usize {
+    match value {
+        Value::String(s) if s == from => {
+            *s = to.to_owned();
+            1
+        }
+        Value::Number(n) if n.to_string() == from => match to.parse::<u64>() {
+            Ok(to) => {
+                *value = Value::from(to);
+                1
+            }
+            Err(_) => 0,
+        },
+        Value::Object(map) => map
+            .values_mut()
+            .map(|child| replace_scalar(child, from, to))
+            .sum(),
+        Value::Array(array) => array
+            .iter_mut()
+            .map(|child| replace_scalar(child, from, to))
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// A game file holding its data as a JSON string, which holds another.
+    fn file(days: u32, state: &str) -> Value {
+        let inner = json!({ "GameDays": days, "Actors": [{ "State": state }] }).to_string();
+        let outer = json!({ "GameState": inner, "Nested": "{\"a\":1,\"b\":2}" }).to_string();
+        json!({ "Version": "0.0.0", "Data": outer })
+    }
+
+    fn diff_expanded(old: &Value, new: &Value) -> Vec<String> {
+        let (mut old, mut new) = (old.clone(), new.clone());
+        expand(&mut old);
+        expand(&mut new);
+        diff(&old, &new).iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn nested_changes_are_found_inside_the_strings() {
+        assert_eq!(
+            diff_expanded(&file(12, "alive"), &file(13, "dead")),
+            [
+                "/Data/GameState/GameDays: 12 -> 13",
+                "/Data/GameState/Actors/0/State: \"alive\" -> \"dead\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_key_order_is_not_a_change() {
+        let mut reordered = file(12, "alive");
+        let data = reordered["Data"]
+            .as_str()
+            .unwrap()
+            .replace(r#"{\"a\":1,\"b\":2}"#, r#"{\"b\":2,\"a\":1}"#);
+        reordered["Data"] = Value::String(data);
+
+        assert_ne!(reordered, file(12, "alive"));
+        assert!(diff_expanded(&file(12, "alive"), &reordered).is_empty());
+    }
+
+    #[test]
+    fn unexpanded_strings_change_whole() {
+        let (old, new) = (file(12, "alive"), file(13, "alive"));
+        let changes = diff(&old, &new);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].pointer, "/Data");
+    }
+
+    #[test]
+    fn added_and_removed_values() {
+        let old = json!({ "a": [1, 2], "b": 1.0 });
+        let new = json!({ "a": [1], "b": 1, "c": "x" });
+
+        assert_eq!(
+            diff(&old, &new)
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>(),
+            ["/a/1: 2 -> (none)", "/c: (none) -> \"x\""]
+        );
+    }
+
+    #[test]
+    fn collapse_undoes_expand() {
+        let original = file(12, "alive");
+        let mut value = original.clone();
+
+        let pointers = expand(&mut value);
+        assert_eq!(pointers, ["/Data", "/Data/GameState", "/Data/Nested"]);
+
+        collapse(&mut value, &pointers);
+        assert_eq!(value, original);
+    }
+}
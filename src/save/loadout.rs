@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+/// How many armour slots the player has.
+pub const ARMOUR_SLOTS: u32 = 10;
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerClothingSystem {
+    #[serde_as(as = "JsonString")]
+    pub player_clothing_system: PlayerClothingSystemInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerClothingSystemInner {
+    /// Item ids of the outfits the player is wearing.
+    pub clothing: Vec<u32>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerArmourSystem {
+    #[serde_as(as = "JsonString")]
+    pub player_armour_system: PlayerArmourSystemInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerArmourSystemInner {
+    pub armour_pieces: Vec<ArmourPiece>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// An armour piece equipped in one of the player's armour slots.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ArmourPiece {
+    pub item_id: u32,
+    pub slot: u32,
+
+    #[serde(rename = "RemainingArmourpoints", with = "super::f32_nan")]
+    pub remaining_armour_points: f32,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+impl ArmourPiece {
+    pub fn new(item_id: u32, slot: u32, remaining_armour_points: f32) -> Self {
+        Self {
+            item_id,
+            slot,
+            remaining_armour_points,
+            ..Default::default()
+        }
+    }
+}
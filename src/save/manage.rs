@@ -0,0 +1,780 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde_json::Value;
+
+use super::{json, GameSetup, GameState, GenericData, SaveType, SelectedSave, PLAYERS_DIR};
+
+const GAME_SETUP_FILE: &str = "GameSetupSaveData.json";
+const GAME_STATE_FILE: &str = "GameStateSaveData.json";
+
+/// Files holding the state of the player who owns the save.
+pub const PLAYER_FILES: &[&str] = &[
+    "PlayerStateSaveData.json",
+    "PlayerInventorySaveData.json",
+    "PlayerClothingSystemSaveData.json",
+    "PlayerArmourSystemSaveData.json",
+];
+
+/// How many backups to keep per save.
+const MAX_BACKUPS: usize = 10;
+
+/// Backups are named by the time they were taken, in this format.
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+/// The steps taken (or that would be taken, in a dry run) by a save operation.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub target: SelectedSave,
+    pub dry_run: bool,
+    pub steps: Vec<String>,
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (steam_id, save_type, name) = &self.target;
+        writeln!(f, "{} save {} for {}:", save_type, name, steam_id)?;
+
+        if self.dry_run {
+            writeln!(f, "Dry run, nothing was changed.")?;
+        }
+
+        for step in self.steps.iter() {
+            writeln!(f, "- {}", step)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The folder of a save.
+pub fn save_path(save_dir: &Path, (steam_id, save_type, name): &SelectedSave) -> PathBuf {
+    type_dir(save_dir, steam_id, *save_type).join(name)
+}
+
+/// The save directory holding a save folder, and which save it is, from the folder's
+/// `<steam id>/<save type>/<save id>` path.
+pub fn locate_save(path: &Path) -> Option<(PathBuf, SelectedSave)> {
+    let path = fs::canonicalize(path).ok()?;
+    let name = path.file_name()?.to_str()?.to_owned();
+    let type_dir = path.parent()?;
+    let save_type = SaveType::ALL
+        .into_iter()
+        .find(|t| type_dir.file_name() == Some(t.as_file().as_ref()))?;
+    let steam_dir = type_dir.parent()?;
+    let steam_id = steam_dir.file_name()?.to_str()?.to_owned();
+
+    Some((steam_dir.parent()?.to_owned(), (steam_id, save_type, name)))
+}
+
+/// The folder holding all saves of one type for a Steam ID.
+fn type_dir(save_dir: &Path, steam_id: &str, save_type: SaveType) -> PathBuf {
+    save_dir.join(steam_id).join(save_type.as_file())
+}
+
+/// All associated saves for a Steam ID.
+#[derive(Default, Clone)]
+pub struct SteamIdSaves {
+    /// The Steam ID corresponding to the child saves.
+    pub id: String,
+
+    /// The saves, categorized by save type (singleplayer/multiplayer).
+    pub saves: HashMap<SaveType, Vec<(String, SystemTime)>>,
+}
+
+impl SteamIdSaves {
+    /// Every save of this Steam ID.
+    pub fn all(&self) -> impl Iterator<Item = SelectedSave> + '_ {
+        self.saves.iter().flat_map(move |(save_type, saves)| {
+            saves
+                .iter()
+                .map(move |(name, _)| (self.id.clone(), *save_type, name.clone()))
+        })
+    }
+}
+
+/// The game's save directory for the current user.
+pub fn default_save_dir() -> Option<PathBuf> {
+    Some(
+        PathBuf::from(std::env::var("UserProfile").ok()?)
+            .join("AppData\\LocalLow\\Endnight\\SonsOfTheForest\\Saves"),
+    )
+}
+
+/// Find all saves in the save directory, grouped by Steam ID.
+pub fn scan_saves(save_dir: &Path) -> Vec<SteamIdSaves> {
+    let steam_ids: Vec<String> = {
+        // get all entries in the saves folder
+        let mut save_dirs = fs::read_dir(save_dir)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().unwrap().is_dir())
+            .map(|entry| {
+                (
+                    entry.file_name(),
+                    entry.metadata().unwrap().modified().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // sort by modified date
+        save_dirs.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        // map to a list of names
+        save_dirs
+            .into_iter()
+            .map(|(name, _)| name.into_string().unwrap())
+            .collect()
+    };
+
+    steam_ids
+        .into_iter()
+        .map(|id| {
+            let mut saves = HashMap::new();
+
+            for save_type in SaveType::ALL {
+                let type_path = save_dir.join(&id).join(save_type.as_file());
+                let mut type_saves = vec![];
+
+                if !type_path.exists() {
+                    continue;
+                }
+
+                // grab all the save folders
+                type_saves.extend(
+                    fs::read_dir(type_path)
+                        .unwrap()
+                        .filter_map(Result::ok)
+                        .filter(|e| e.file_type().unwrap().is_dir())
+                        .map(|e| {
+                            (
+                                e.file_name().into_string().unwrap(),
+                                e.metadata().unwrap().modified().unwrap(),
+                            )
+                        }),
+                );
+
+                type_saves.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+                saves.insert(save_type, type_saves);
+            }
+
+            SteamIdSaves { id, saves }
+        })
+        .collect()
+}
+
+/// Allocate a numeric save id that isn't used in a save type folder.
+pub fn allocate_save_id(save_dir: &Path, steam_id: &str, save_type: SaveType) -> String {
+    let dir = type_dir(save_dir, steam_id, save_type);
+
+    let max_id = fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|e| e.file_name().into_string().ok()?.parse::<u64>().ok())
+                .max()
+        })
+        .unwrap_or_default();
+
+    match max_id {
+        Some(id) => (id + 1).to_string(),
+        // the game uses ten digit ids, a timestamp is a good starting point
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |d| d.as_secs())
+            .to_string(),
+    }
+}
+
+/// Copy a save to another save id, allocating a new id if none is given. Returns the new
+/// save.
+pub fn clone_save(
+    save_dir: &Path,
+    (steam_id, save_type, name): &SelectedSave,
+    target: Option<(&str, SaveType, &str)>,
+) -> io::Result<SelectedSave> {
+    let (target_steam_id, target_type, target_name) = match target {
+        Some((id, save_type, name)) => (id.to_owned(), save_type, name.to_owned()),
+        None => (
+            steam_id.to_owned(),
+            *save_type,
+            allocate_save_id(save_dir, steam_id, *save_type),
+        ),
+    };
+
+    let from = type_dir(save_dir, steam_id, *save_type).join(name);
+    let to = type_dir(save_dir, &target_steam_id, target_type).join(&target_name);
+
+    if target_name.parse::<u64>().is_err() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "save ids must be numeric",
+        ));
+    }
+
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("save {} already exists", target_name),
+        ));
+    }
+
+    // a half made copy would show up in the game as a broken save
+    if let Err(e) = copy_dir(&from, &to).and_then(|_| update_save_id(&to, name, &target_name)) {
+        let _ = fs::remove_dir_all(&to);
+        return Err(e);
+    }
+
+    Ok((target_steam_id, target_type, target_name))
+}
+
+/// Move a save to another save id in the same folder.
+pub fn rename_save(
+    save_dir: &Path,
+    (steam_id, save_type, name): &SelectedSave,
+    new_name: &str,
+) -> io::Result<SelectedSave> {
+    let dir = type_dir(save_dir, steam_id, *save_type);
+    let to = dir.join(new_name);
+
+    if new_name.parse::<u64>().is_err() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "save ids must be numeric",
+        ));
+    }
+
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("save {} already exists", new_name),
+        ));
+    }
+
+    fs::rename(dir.join(name), &to)?;
+    if let Err(e) = update_save_id(&to, name, new_name) {
+        // put the save back, so it isn't left under an id its settings don't match
+        fs::rename(&to, dir.join(name))?;
+        return Err(e);
+    }
+
+    Ok((steam_id.to_owned(), *save_type, new_name.to_owned()))
+}
+
+/// Delete a save folder.
+pub fn delete_save(save_dir: &Path, (steam_id, save_type, name): &SelectedSave) -> io::Result<()> {
+    fs::remove_dir_all(type_dir(save_dir, steam_id, *save_type).join(name))
+}
+
+/// Recursively copy a directory.
+pub fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Every file in a folder and its subfolders, relative to the folder.
+fn files_in(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![PathBuf::new()];
+
+    while let Some(sub_dir) = dirs.pop() {
+        for entry in fs::read_dir(dir.join(&sub_dir))? {
+            let entry = entry?;
+            let name = sub_dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                dirs.push(name);
+            } else {
+                files.push(name);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The folder in a backup directory holding a save's backups, named after the save like
+/// the game's own folders: `<steam id>/<save type>/<save id>`. Backups are kept outside the
+/// game's save folders so the game and save operations never see them.
+fn save_backups_dir(backup_dir: &Path, path: &Path) -> PathBuf {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let mut names = path
+        .components()
+        .rev()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .take(3)
+        .collect::<Vec<_>>();
+    names.reverse();
+
+    names
+        .into_iter()
+        .fold(backup_dir.to_owned(), |dir, name| dir.join(name))
+}
+
+/// Copy a save's files and the other players' data into a new backup in a backup directory
+/// before they are overwritten, removing the oldest backups past [`MAX_BACKUPS`].
+pub fn backup_save(backup_dir: &Path, path: &Path) -> io::Result<()> {
+    let mut files = vec![];
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            files.push(entry);
+        }
+    }
+
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let backup = save_backups_dir(backup_dir, path)
+        .join(Local::now().format(BACKUP_TIME_FORMAT).to_string());
+    fs::create_dir_all(&backup)?;
+    for entry in files {
+        fs::copy(entry.path(), backup.join(entry.file_name()))?;
+    }
+    if path.join(PLAYERS_DIR).is_dir() {
+        copy_dir(&path.join(PLAYERS_DIR), &backup.join(PLAYERS_DIR))?;
+    }
+
+    for old in backups(backup_dir, path).into_iter().skip(MAX_BACKUPS) {
+        fs::remove_dir_all(old)?;
+    }
+
+    Ok(())
+}
+
+/// A save's backups in a backup directory, newest first.
+pub fn backups(backup_dir: &Path, path: &Path) -> Vec<PathBuf> {
+    let mut backups = fs::read_dir(save_backups_dir(backup_dir, path))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+
+    // named by time, so they sort by name
+    backups.sort_by(|a, b| b.cmp(a));
+    backups
+}
+
+/// When a backup was taken.
+pub fn backup_time(backup: &Path) -> Option<DateTime<Local>> {
+    let name = backup.file_name()?.to_str()?;
+    let time = NaiveDateTime::parse_from_str(name, BACKUP_TIME_FORMAT).ok()?;
+    time.and_local_timezone(Local).earliest()
+}
+
+/// Replace a save's files with those of one of its backups. The current files are backed
+/// up first, so a restore can be undone the same way.
+pub fn restore_backup(backup_dir: &Path, path: &Path, backup: &Path) -> io::Result<()> {
+    replace_files(Some(backup_dir), path, backup)
+}
+
+/// Replace a save's files with those of another slot of the same world, see
+/// [`world_slots`]. The current files are backed up first if a backup directory is given.
+pub fn restore_slot(backup_dir: Option<&Path>, path: &Path, slot: &Path) -> io::Result<()> {
+    replace_files(backup_dir, path, slot)?;
+
+    match (slot.file_name(), path.file_name()) {
+        (Some(old_id), Some(new_id)) => {
+            update_save_id(path, &old_id.to_string_lossy(), &new_id.to_string_lossy())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Other slots of the same world as a save, such as the game's autosaves. Slots of the same
+/// Steam ID and save type are taken to be the same world when their game setup is the same
+/// apart from the save id.
+pub fn world_slots(path: &Path) -> Vec<PathBuf> {
+    let Some(setup) = world_setup(path) else {
+        return vec![];
+    };
+
+    let mut slots = path
+        .parent()
+        .and_then(|type_dir| fs::read_dir(type_dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|slot| slot.file_name() != path.file_name())
+        .filter(|slot| world_setup(slot).as_ref() == Some(&setup))
+        .collect::<Vec<_>>();
+
+    slots.sort();
+    slots
+}
+
+/// Whether two saves are of the same world, by their game setup apart from the save id.
+pub fn same_world(a: &Path, b: &Path) -> bool {
+    world_setup(a).is_some_and(|setup| world_setup(b) == Some(setup))
+}
+
+/// A save's game setup settings, without those holding its save id.
+fn world_setup(path: &Path) -> Option<Vec<Value>> {
+    let name = path.file_name()?.to_string_lossy();
+    let setup: GenericData<GameSetup> =
+        json::read_optional_file(&path.join(GAME_SETUP_FILE)).ok()??;
+
+    Some(
+        setup
+            .data
+            .game_setup
+            .settings
+            .iter()
+            .filter(|setting| setting.string_value() != Some(&name))
+            .filter_map(|setting| serde_json::to_value(setting).ok())
+            .collect(),
+    )
+}
+
+/// Replace a save's files with those in another folder, backing up the current files first
+/// if a backup directory is given. The other players' data is replaced too if the other
+/// folder has it, and left alone otherwise, as backups taken before it was backed up don't.
+fn replace_files(backup_dir: Option<&Path>, path: &Path, from: &Path) -> io::Result<()> {
+    // read the files before backing up, which may remove the oldest backups
+    let mut files = vec![];
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            files.push((entry.file_name(), fs::read(entry.path())?));
+        }
+    }
+    let mut players = None;
+    if from.join(PLAYERS_DIR).is_dir() {
+        let dir = from.join(PLAYERS_DIR);
+        players = Some(
+            files_in(&dir)?
+                .into_iter()
+                .map(|name| {
+                    let contents = fs::read(dir.join(&name))?;
+                    Ok((name, contents))
+                })
+                .collect::<io::Result<Vec<_>>>()?,
+        );
+    }
+
+    if files.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} has no files", from.display()),
+        ));
+    }
+
+    if let Some(backup_dir) = backup_dir {
+        backup_save(backup_dir, path)?;
+    }
+
+    // files the save has that the other folder doesn't go, as they weren't part of it
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && !files.iter().any(|(name, _)| *name == entry.file_name())
+        {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    for (name, contents) in files {
+        fs::write(path.join(name), contents)?;
+    }
+
+    if let Some(players) = players {
+        let dir = path.join(PLAYERS_DIR);
+        if dir.is_dir() {
+            fs::remove_dir_all(&dir)?;
+        }
+        for (name, contents) in players {
+            let file = dir.join(name);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file, contents)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Update game setup settings that refer to the old save id.
+fn update_save_id(path: &Path, old_id: &str, new_id: &str) -> io::Result<()> {
+    replace_setup_values(path, old_id, new_id)
+}
+
+/// Replace a string value in every game setup setting holding it.
+fn replace_setup_values(path: &Path, old: &str, new: &str) -> io::Result<()> {
+    let setup_path = path.join(GAME_SETUP_FILE);
+    if !setup_path.is_file() {
+        return Ok(());
+    }
+
+    let mut setup: GenericData<GameSetup> = json::read_file(&setup_path)?;
+
+    let mut changed = false;
+    for setting in setup.data.game_setup.settings.iter_mut() {
+        if setting.string_value() == Some(old) {
+            setting.set_string_value(new);
+            changed = true;
+        }
+    }
+
+    if changed {
+        json::write_file(&setup_path, &setup)?;
+    }
+
+    Ok(())
+}
+
+/// Convert a singleplayer save to a multiplayer host save or back, copying it into the
+/// other save type folder.
+pub fn convert_save(
+    save_dir: &Path,
+    selected: &SelectedSave,
+    target_type: SaveType,
+    dry_run: bool,
+) -> io::Result<Report> {
+    let (steam_id, save_type, name) = selected;
+
+    if *save_type == SaveType::MultiplayerClient || target_type == SaveType::MultiplayerClient {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "multiplayer client saves hold no world and can't be converted",
+        ));
+    }
+
+    if *save_type == target_type {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("save is already {}", target_type),
+        ));
+    }
+
+    let from = type_dir(save_dir, steam_id, *save_type).join(name);
+
+    // keep the save id if it's free in the target folder
+    let target_name = if type_dir(save_dir, steam_id, target_type)
+        .join(name)
+        .exists()
+    {
+        allocate_save_id(save_dir, steam_id, target_type)
+    } else {
+        name.to_owned()
+    };
+    let to = type_dir(save_dir, steam_id, target_type).join(&target_name);
+
+    let mut steps = vec![format!(
+        "Copy {} save {} to {} save {}.",
+        save_type, name, target_type, target_name
+    )];
+
+    // the owner's player files carry over as the host's (or singleplayer's) player
+    for file in PLAYER_FILES {
+        steps.push(if from.join(file).is_file() {
+            format!("Keep {} as the owner's player data.", file)
+        } else {
+            format!(
+                "{} is missing, the game will start the owner with fresh player data.",
+                file
+            )
+        });
+    }
+
+    // a singleplayer world has no other players, so the data the host kept for them goes
+    let other_players = fs::read_dir(from.join(PLAYERS_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    if target_type == SaveType::Singleplayer {
+        for steam_id in other_players.iter() {
+            steps.push(format!("Remove the data of player {}.", steam_id));
+        }
+    }
+
+    let game_state: GenericData<GameState> = json::read_file(&from.join(GAME_STATE_FILE))?;
+    let old_game_type = game_state.data.game_state.game_type.clone();
+    steps.push(format!(
+        "Set game type from {:?} to {:?}.",
+        old_game_type,
+        target_type.as_file()
+    ));
+
+    let setup: Option<GenericData<GameSetup>> =
+        json::read_optional_file(&from.join(GAME_SETUP_FILE))?;
+    for setting in setup
+        .iter()
+        .flat_map(|setup| setup.data.game_setup.settings.iter())
+        .filter(|s| s.string_value() == Some(save_type.as_file()))
+    {
+        steps.push(format!(
+            "Set game setup {} from {:?} to {:?}.",
+            setting.name,
+            save_type.as_file(),
+            target_type.as_file()
+        ));
+    }
+
+    if !dry_run {
+        copy_dir(&from, &to)?;
+        update_save_id(&to, name, &target_name)?;
+        replace_setup_values(&to, save_type.as_file(), target_type.as_file())?;
+
+        if target_type == SaveType::Singleplayer && !other_players.is_empty() {
+            fs::remove_dir_all(to.join(PLAYERS_DIR))?;
+        }
+
+        let mut game_state = game_state;
+        game_state.data.game_state.game_type = target_type.as_file().to_owned();
+        json::write_file(&to.join(GAME_STATE_FILE), &game_state)?;
+    }
+
+    Ok(Report {
+        target: (steam_id.to_owned(), target_type, target_name),
+        dry_run,
+        steps,
+    })
+}
+
+/// Copy a save to another Steam ID's folder, rewriting the Steam ID wherever it is embedded
+/// in the save and moving the data a host save keeps for the other players to match. A new
+/// save id is allocated if the target already has a save with this id.
+pub fn transfer_save(
+    save_dir: &Path,
+    selected: &SelectedSave,
+    target_steam_id: &str,
+    dry_run: bool,
+) -> io::Result<Report> {
+    let (steam_id, save_type, name) = selected;
+
+    if target_steam_id.parse::<u64>().is_err() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Steam IDs must be numeric",
+        ));
+    }
+
+    if target_steam_id == steam_id {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "save already belongs to this Steam ID",
+        ));
+    }
+
+    let from = type_dir(save_dir, steam_id, *save_type).join(name);
+
+    let target_name = if type_dir(save_dir, target_steam_id, *save_type)
+        .join(name)
+        .exists()
+    {
+        allocate_save_id(save_dir, target_steam_id, *save_type)
+    } else {
+        name.to_owned()
+    };
+    let to = type_dir(save_dir, target_steam_id, *save_type).join(&target_name);
+
+    let mut steps = vec![format!(
+        "Copy {} save {} from {} to {} save {} for {}.",
+        save_type, name, steam_id, save_type, target_name, target_steam_id
+    )];
+
+    if target_name != *name {
+        steps.push(format!(
+            "Save id {} is taken for {}, using {}.",
+            name, target_steam_id, target_name
+        ));
+    }
+
+    // the data the host kept for the new owner as another player is superseded by the
+    // owner's own player files, and that kept for the old owner (if any) is theirs now
+    let players = Path::new(PLAYERS_DIR);
+    let superseded = players.join(target_steam_id);
+    if from.join(&superseded).is_dir() {
+        steps.push(format!(
+            "Remove the data of player {}, who owns the save now.",
+            target_steam_id
+        ));
+    }
+    let re_keyed = from.join(players.join(steam_id)).is_dir();
+    if re_keyed {
+        steps.push(format!(
+            "Move the data of player {} to player {}.",
+            steam_id, target_steam_id
+        ));
+    }
+
+    // find embedded Steam IDs in every file of the save, including the other players' data
+    let mut rewrites = vec![];
+    for file in files_in(&from)? {
+        if file.extension().map_or(true, |e| e != "json") || file.starts_with(&superseded) {
+            continue;
+        }
+
+        let mut value: Value = json::read_file(&from.join(&file))?;
+        let pointers = json::expand(&mut value);
+        let replaced = json::replace_scalar(&mut value, steam_id, target_steam_id);
+
+        if replaced > 0 {
+            steps.push(format!(
+                "Rewrite {} Steam ID references in {}.",
+                replaced,
+                file.display()
+            ));
+
+            json::collapse(&mut value, &pointers);
+            rewrites.push((file, value));
+        }
+    }
+
+    if rewrites.is_empty() {
+        steps.push("No embedded Steam IDs to rewrite.".to_owned());
+    }
+
+    if !dry_run {
+        copy_dir(&from, &to)?;
+
+        if to.join(&superseded).is_dir() {
+            fs::remove_dir_all(to.join(&superseded))?;
+        }
+
+        // the rewrites were made from the original files, so the save id is updated after
+        for (file, value) in rewrites {
+            json::write_file(&to.join(file), &value)?;
+        }
+
+        if re_keyed {
+            fs::rename(to.join(players.join(steam_id)), to.join(&superseded))?;
+        }
+
+        update_save_id(&to, name, &target_name)?;
+    }
+
+    Ok(Report {
+        target: (target_steam_id.to_owned(), *save_type, target_name),
+        dry_run,
+        steps,
+    })
+}
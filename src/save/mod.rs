@@ -1,15 +1,47 @@
+mod constructions;
+mod document;
+pub mod export;
+mod game_setup;
 mod game_state;
+mod inventory;
+mod items;
+pub mod json;
+mod loadout;
+pub mod manage;
+mod npcs;
+pub mod path;
+mod player_state;
+mod players;
 mod save_data;
+mod storage;
+mod vector;
+mod world_items;
+mod ziplines;
 
 use std::{
+    collections::hash_map::DefaultHasher,
     fmt::Display,
-    fs::File,
+    fs::{self, File},
+    hash::Hasher,
     io::{self, BufReader},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 
+pub use constructions::*;
+pub use game_setup::*;
 pub use game_state::*;
+pub use inventory::*;
+pub use items::*;
+pub use loadout::*;
+pub use npcs::*;
+pub use player_state::*;
+pub use players::*;
 pub use save_data::*;
+pub use storage::*;
+pub use vector::*;
+pub use world_items::*;
+pub use ziplines::*;
 
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +58,32 @@ pub struct GenericData<T> {
 pub struct Save {
     pub game_state: GenericData<GameState>,
     pub save_data: GenericData<SaveData>,
+    pub game_setup: Option<GenericData<GameSetup>>,
+    pub player_state: Option<GenericData<PlayerState>>,
+    pub player_inventory: Option<GenericData<PlayerInventory>>,
+    pub clothing: Option<GenericData<PlayerClothingSystem>>,
+    pub armour: Option<GenericData<PlayerArmourSystem>>,
+    pub constructions: Option<GenericData<Constructions>>,
+    pub screw_structures: Option<GenericData<ScrewStructureInstances>>,
+    pub storages: Option<GenericData<Storages>>,
+    pub world_items: Option<GenericData<WorldItemManager>>,
+    pub zip_lines: Option<GenericData<ZipLineManager>>,
+
+    /// Other players' data kept by a multiplayer host save. These are read and written
+    /// separately, and only for host saves.
+    pub players: Vec<PlayerData>,
+
+    /// Optional files that are there but couldn't be parsed. They are left out of the save
+    /// and written back as they were read.
+    pub unparsed: Vec<UnparsedFile>,
+}
+
+/// An optional save file that couldn't be parsed, kept as it was read.
+#[derive(Debug, Clone)]
+pub struct UnparsedFile {
+    pub file: &'static str,
+    pub error: String,
+    contents: Vec<u8>,
 }
 
 /// A save type.
@@ -46,7 +104,28 @@ impl Display for SaveType {
     }
 }
 
+impl FromStr for SaveType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "singleplayer" => Ok(Self::Singleplayer),
+            "multiplayer" => Ok(Self::Multiplayer),
+            "multiplayer-client" | "multiplayerclient" => Ok(Self::MultiplayerClient),
+            _ => Err(format!("unknown save type: {}", s)),
+        }
+    }
+}
+
 impl SaveType {
+    pub const ALL: [SaveType; 3] = [
+        SaveType::Singleplayer,
+        SaveType::Multiplayer,
+        SaveType::MultiplayerClient,
+    ];
+
+    /// The name of the folder holding saves of this type, which is also the `GameType` the
+    /// game stores in them.
     pub fn as_file(&self) -> &'static str {
         match self {
             Self::Singleplayer => "SinglePlayer",
@@ -63,12 +142,30 @@ pub struct SaveInstance {
     pub path: SelectedSave,
     pub save: Save,
     pub tools: SaveTools,
+    /// The save's fingerprint when it was last read or written.
+    saved: u64,
 }
 
 impl SaveInstance {
     pub fn new(path: SelectedSave, save: Save) -> Self {
         let tools = SaveTools::new(&save);
-        Self { path, save, tools }
+        let saved = save.fingerprint();
+        Self {
+            path,
+            save,
+            tools,
+            saved,
+        }
+    }
+
+    /// Note that the save as it is now has been written.
+    pub fn mark_saved(&mut self) {
+        self.saved = self.save.fingerprint();
+    }
+
+    /// Whether the save has been edited since it was last read or written.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.save.fingerprint() != self.saved
     }
 }
 
@@ -84,42 +181,163 @@ macro_rules! get_type_id_methods {
     };
 }
 
+/// Invoke a macro with every file of a save, as `required` or `optional` followed by the
+/// field it is stored in, its type and its file name.
+macro_rules! with_save_files {
+    ($m:ident) => {
+        $m!(
+            required game_state: $crate::save::GameState => "GameStateSaveData.json",
+            required save_data: $crate::save::SaveData => "SaveData.json",
+            optional game_setup: $crate::save::GameSetup => "GameSetupSaveData.json",
+            optional player_state: $crate::save::PlayerState => "PlayerStateSaveData.json",
+            optional player_inventory: $crate::save::PlayerInventory =>
+                "PlayerInventorySaveData.json",
+            optional clothing: $crate::save::PlayerClothingSystem =>
+                "PlayerClothingSystemSaveData.json",
+            optional armour: $crate::save::PlayerArmourSystem => "PlayerArmourSystemSaveData.json",
+            optional constructions: $crate::save::Constructions => "ConstructionsSaveData.json",
+            optional screw_structures: $crate::save::ScrewStructureInstances =>
+                "ScrewStructureInstancesSaveData.json",
+            optional storages: $crate::save::Storages => "StorageSaveData.json",
+            optional world_items: $crate::save::WorldItemManager => "WorldItemManagerSaveData.json",
+            optional zip_lines: $crate::save::ZipLineManager => "ZipLineManagerSaveData.json",
+        )
+    };
+}
+
+pub(crate) use with_save_files;
+
 impl Save {
+    /// Read a save folder. Only the required files failing to parse fails the read; optional
+    /// files that don't parse are kept in [`Save::unparsed`].
     pub fn read(path: PathBuf) -> io::Result<Self> {
+        let mut unparsed = vec![];
+
         macro_rules! load_file {
-            ($name:ident : $type:ty => $file:literal) => {
+            (required $name:ident : $type:ty => $file:literal) => {
                 let $name: GenericData<$type> = {
                     let file = File::open(path.join($file))?;
-                    serde_json::from_reader(BufReader::new(file))
-                        .expect(concat!("failed to parse ", stringify!($name)))
+                    serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(concat!("failed to parse ", $file, ": {}"), e),
+                        )
+                    })?
+                };
+            };
+            // a file the models don't fit is kept as it is rather than failing the whole save
+            (optional $name:ident : $type:ty => $file:literal) => {
+                let $name: Option<GenericData<$type>> = if path.join($file).is_file() {
+                    let contents = fs::read(path.join($file))?;
+                    match serde_json::from_slice(&contents) {
+                        Ok($name) => Some($name),
+                        Err(e) => {
+                            unparsed.push(UnparsedFile {
+                                file: $file,
+                                error: e.to_string(),
+                                contents,
+                            });
+                            None
+                        }
+                    }
+                } else {
+                    None
                 };
             };
         }
 
-        load_file!(game_state: GameState => "GameStateSaveData.json");
-        load_file!(save_data: SaveData => "SaveData.json");
+        macro_rules! load_files {
+            ($($kind:ident $name:ident : $type:ty => $file:literal,)*) => {{
+                $(load_file!($kind $name: $type => $file);)*
 
-        Ok(Self {
-            game_state,
-            save_data,
-        })
+                Ok(Self {
+                    $($name,)*
+                    players: vec![],
+                    unparsed,
+                })
+            }};
+        }
+
+        with_save_files!(load_files)
     }
 
-    pub fn write(&self, path: PathBuf) -> io::Result<()> {
+    /// Write every loaded file, backing up the previous files into a backup directory first
+    /// if one is given.
+    pub fn write(&self, path: PathBuf, backup_dir: Option<&Path>) -> io::Result<()> {
+        if let Some(backup_dir) = backup_dir {
+            manage::backup_save(backup_dir, &path)?;
+        }
+
         macro_rules! write_file {
-            ($name:ident => $file:literal) => {{
+            (required $name:ident => $file:literal) => {{
                 let file = File::create(path.join($file))?;
                 serde_json::to_writer(file, &self.$name)
                     .expect(concat!("failed to write", stringify!($name)));
             };};
+            (optional $name:ident => $file:literal) => {{
+                if let Some(ref $name) = self.$name {
+                    let file = File::create(path.join($file))?;
+                    serde_json::to_writer(file, $name)
+                        .expect(concat!("failed to write", stringify!($name)));
+                }
+            };};
+        }
+
+        macro_rules! write_files {
+            ($($kind:ident $name:ident : $type:ty => $file:literal,)*) => {
+                $(write_file!($kind $name => $file);)*
+            };
+        }
+
+        with_save_files!(write_files);
+
+        for unparsed in self.unparsed.iter() {
+            fs::write(path.join(unparsed.file), &unparsed.contents)?;
         }
 
-        write_file!(game_state => "GameStateSaveData.json");
-        write_file!(save_data => "SaveData.json");
+        // only players whose data was edited are written, into the backup taken above
+        for player in self.players.iter() {
+            player.write(&path)?;
+        }
 
         Ok(())
     }
 
+    /// A hash of the contents of the save's files and the other players' data, which changes
+    /// whenever they are edited.
+    pub fn fingerprint(&self) -> u64 {
+        struct HashWriter(DefaultHasher);
+
+        impl io::Write for HashWriter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.write(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = HashWriter(DefaultHasher::new());
+
+        macro_rules! hash_files {
+            ($($kind:ident $name:ident : $type:ty => $file:literal,)*) => {
+                $(serde_json::to_writer(&mut writer, &self.$name)
+                    .expect(concat!("failed to hash ", stringify!($name)));)*
+            };
+        }
+
+        with_save_files!(hash_files);
+
+        for player in self.players.iter() {
+            serde_json::to_writer(&mut writer, &(&player.state, &player.inventory))
+                .expect("failed to hash player data");
+        }
+
+        writer.0.finish()
+    }
+
     get_type_id_methods!(
         actor, actor_mut: Actor =>
             save_data.data.vail_world_sim.actors
@@ -129,6 +347,43 @@ impl Save {
         kill_stat, kill_stat_mut: KillStat =>
             save_data.data.vail_world_sim.kill_stats_list
     );
+
+    /// The player's position, if the player state was loaded.
+    pub fn player_position(&self) -> Option<Vector3> {
+        self.player_state.as_ref()?.data.player_state.position()
+    }
+
+    /// Move the player, returning false if the player state has no position to change.
+    pub fn set_player_position(&mut self, position: Vector3) -> bool {
+        self.player_state
+            .as_mut()
+            .is_some_and(|state| state.data.player_state.set_position(position))
+    }
+
+    /// The positions of every built structure, structure element and blueprint structure.
+    pub fn structure_positions(&self) -> Vec<Vector3> {
+        let mut positions = vec![];
+
+        if let Some(constructions) = &self.constructions {
+            for structure in constructions.data.constructions.structures.iter() {
+                positions.push(structure.position);
+                positions.extend(structure.elements.iter().map(|e| e.position));
+            }
+        }
+
+        if let Some(screw_structures) = &self.screw_structures {
+            positions.extend(
+                screw_structures
+                    .data
+                    .screw_structure_instances
+                    .structures
+                    .iter()
+                    .map(|s| s.pos),
+            );
+        }
+
+        positions
+    }
 }
 
 mod f32_nan {
@@ -188,6 +443,20 @@ mod f32_nan {
         {
             Ok(v as f32)
         }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v as f32)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v as f32)
+        }
     }
 
     pub fn serialize<S: Serializer>(n: &f32, s: S) -> Result<S::Ok, S::Error> {
@@ -202,3 +471,21 @@ mod f32_nan {
         d.deserialize_any(F32NanVisitor)
     }
 }
+
+mod f32_nan_option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    struct F32Nan(#[serde(with = "super::f32_nan")] f32);
+
+    pub fn serialize<S: Serializer>(n: &Option<f32>, s: S) -> Result<S::Ok, S::Error> {
+        match n {
+            Some(n) => super::f32_nan::serialize(n, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f32>, D::Error> {
+        Ok(Option::<F32Nan>::deserialize(d)?.map(|F32Nan(n)| n))
+    }
+}
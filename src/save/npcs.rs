@@ -0,0 +1,287 @@
+//! Unique NPCs, such as the companions, described by data so every one is handled the
+//! same way. Supporting another NPC only takes adding its definition to [`NPCS`].
+
+use std::fmt::Display;
+
+use super::{
+    Actor, ActorStats, GameStateInner, Save, Vector3, ACTOR_STATE_ALIVE, ACTOR_STATE_DEAD,
+};
+
+/// How far from the player an NPC's new actor is placed, in meters.
+const SPAWN_OFFSET: Vector3 = Vector3 {
+    x: 2.0,
+    y: 0.0,
+    z: 2.0,
+};
+
+/// A unique NPC: there is only ever one actor of its type.
+#[derive(Debug)]
+pub struct NpcDefinition {
+    pub name: &'static str,
+    pub type_id: u32,
+
+    /// The game state flag marking the NPC dead, e.g. `IsRobbyDead`.
+    pub is_flagged_dead: fn(&GameStateInner) -> bool,
+    pub set_flagged_dead: fn(&mut GameStateInner, bool),
+
+    pub max_health: f32,
+
+    /// The actor state of the NPC when alive.
+    pub default_state: u32,
+}
+
+pub const NPCS: &[NpcDefinition] = &[
+    NpcDefinition {
+        name: "Kelvin",
+        type_id: 9,
+        is_flagged_dead: |state| state.is_robby_dead,
+        set_flagged_dead: |state, dead| state.is_robby_dead = dead,
+        max_health: 100.0,
+        default_state: ACTOR_STATE_ALIVE,
+    },
+    NpcDefinition {
+        name: "Virginia",
+        type_id: 10,
+        is_flagged_dead: |state| state.is_virginia_dead,
+        set_flagged_dead: |state, dead| state.is_virginia_dead = dead,
+        max_health: 120.0,
+        default_state: ACTOR_STATE_ALIVE,
+    },
+];
+
+/// The definition of a unique NPC by type id.
+pub fn npc(type_id: u32) -> Option<&'static NpcDefinition> {
+    NPCS.iter().find(|npc| npc.type_id == type_id)
+}
+
+/// What happened to an NPC's actor when it was resurrected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resurrection {
+    /// The existing actor was revived.
+    Revived,
+    /// The actor was missing, so a new one was added at a position.
+    Added(Vector3),
+    /// The actor was missing and couldn't be added, as the player's position is unknown.
+    NoActor,
+}
+
+impl Display for Resurrection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Revived => f.write_str("revived the existing actor"),
+            Self::Added(position) => {
+                write!(f, "actor was missing, added a new one at {}", position)
+            }
+            Self::NoActor => f.write_str(
+                "actor is missing and the player's position is unknown, so none was added",
+            ),
+        }
+    }
+}
+
+impl NpcDefinition {
+    /// Whether the NPC is dead by any account: the game state flag, the actor's state or
+    /// health, or the player having killed them.
+    pub fn is_dead(&self, save: &Save) -> bool {
+        if (self.is_flagged_dead)(&save.game_state.data.game_state) {
+            return true;
+        }
+
+        if let Some(actor) = save.actor(self.type_id) {
+            if actor.state == ACTOR_STATE_DEAD {
+                return true;
+            }
+
+            if let Some(ActorStats { health, .. }) = actor.stats {
+                if health.is_nan() || health <= 0.0 {
+                    return true;
+                }
+            }
+        }
+
+        save.kill_stat(self.type_id)
+            .is_some_and(|kill| kill.player_killed != 0)
+    }
+
+    /// Bring the NPC back: clear the dead flag and kill stat, and revive their actor with
+    /// full health or add a fresh one near the player if the save has none.
+    pub fn resurrect(&self, save: &mut Save) -> Resurrection {
+        (self.set_flagged_dead)(&mut save.game_state.data.game_state, false);
+
+        if let Some(kill) = save.kill_stat_mut(self.type_id) {
+            kill.player_killed = 0;
+        }
+
+        if let Some(actor) = save.actor_mut(self.type_id) {
+            actor.state = self.default_state;
+            if let Some(stats) = &mut actor.stats {
+                stats.health = self.max_health;
+            }
+            return Resurrection::Revived;
+        }
+
+        let Some(player) = save.player_position() else {
+            return Resurrection::NoActor;
+        };
+
+        let position = Vector3::new(
+            player.x + SPAWN_OFFSET.x,
+            player.y + SPAWN_OFFSET.y,
+            player.z + SPAWN_OFFSET.z,
+        );
+
+        let mut actor = Actor::new(self.type_id, position, self.max_health);
+        actor.state = self.default_state;
+        save.save_data.data.vail_world_sim.actors.push(actor);
+
+        Resurrection::Added(position)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use crate::save::{GameState, GenericData, PlayerState, SaveData};
+
+    pub(crate) const KELVIN: &NpcDefinition = &NPCS[0];
+
+    fn data<T>(data: T) -> GenericData<T> {
+        GenericData {
+            version: "0.0.0".to_owned(),
+            data,
+        }
+    }
+
+    /// A save holding only the given actors, with the player at 10,20,30 if `player` is set.
+    pub(crate) fn save(actors: Vec<Actor>, player: bool) -> Save {
+        let mut save_data = SaveData::default();
+        save_data.vail_world_sim.actors = actors;
+
+        let player_state = player.then(|| {
+            serde_json::from_value::<GenericData<PlayerState>>(serde_json::json!({
+                "Version": "0.0.0",
+                "Data": {
+                    "PlayerState": r#"{"_entries":[{"Name":"player.position","FloatArrayValue":[10.0,20.0,30.0]}]}"#
+                }
+            }))
+            .unwrap()
+        });
+
+        Save {
+            game_state: data(GameState::default()),
+            save_data: data(save_data),
+            game_setup: None,
+            player_state,
+            player_inventory: None,
+            clothing: None,
+            armour: None,
+            constructions: None,
+            screw_structures: None,
+            storages: None,
+            world_items: None,
+            zip_lines: None,
+            players: vec![],
+            unparsed: vec![],
+        }
+    }
+
+    /// An empty scratch directory for a test, removed again when dropped.
+    pub(crate) struct Scratch(pub(crate) PathBuf);
+
+    impl Scratch {
+        pub(crate) fn new(test: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("sotfsavetools-{}-{}", test, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    pub(crate) fn kelvin(state: u32, health: f32) -> Actor {
+        let mut actor = Actor::new(KELVIN.type_id, Vector3::new(0.0, 0.0, 0.0), health);
+        actor.state = state;
+        actor
+    }
+
+    #[test]
+    fn alive() {
+        let save = save(vec![kelvin(ACTOR_STATE_ALIVE, 100.0)], false);
+        assert!(!KELVIN.is_dead(&save));
+    }
+
+    #[test]
+    fn dead_by_flag() {
+        let mut save = save(vec![kelvin(ACTOR_STATE_ALIVE, 100.0)], false);
+        (KELVIN.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+        assert!((KELVIN.is_flagged_dead)(&save.game_state.data.game_state));
+        assert!(KELVIN.is_dead(&save));
+    }
+
+    #[test]
+    fn dead_by_state() {
+        let save = save(vec![kelvin(ACTOR_STATE_DEAD, 100.0)], false);
+        assert!(KELVIN.is_dead(&save));
+    }
+
+    #[test]
+    fn dead_by_nan_health() {
+        let save = save(vec![kelvin(ACTOR_STATE_ALIVE, f32::NAN)], false);
+        assert!(KELVIN.is_dead(&save));
+    }
+
+    #[test]
+    fn resurrect_existing_actor() {
+        let mut save = save(vec![kelvin(ACTOR_STATE_DEAD, f32::NAN)], true);
+        (KELVIN.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+
+        assert_eq!(KELVIN.resurrect(&mut save), Resurrection::Revived);
+        assert!(!KELVIN.is_dead(&save));
+        assert_eq!(save.save_data.data.vail_world_sim.actors.len(), 1);
+
+        let actor = save.actor(KELVIN.type_id).unwrap();
+        assert_eq!(actor.state, KELVIN.default_state);
+        assert_eq!(actor.stats.as_ref().unwrap().health, KELVIN.max_health);
+    }
+
+    #[test]
+    fn resurrect_missing_actor() {
+        let mut save = save(vec![], true);
+        (KELVIN.set_flagged_dead)(&mut save.game_state.data.game_state, true);
+
+        let position = Vector3::new(12.0, 20.0, 32.0);
+        assert_eq!(KELVIN.resurrect(&mut save), Resurrection::Added(position));
+        assert!(!KELVIN.is_dead(&save));
+
+        let actor = save.actor(KELVIN.type_id).unwrap();
+        assert_eq!(actor.state, KELVIN.default_state);
+        assert_eq!(actor.position, Some(position));
+        assert_eq!(actor.stats.as_ref().unwrap().health, KELVIN.max_health);
+        // the game fills in what it needs, such as the outfit, when it loads the actor
+        assert_eq!(
+            serde_json::to_value(actor).unwrap(),
+            serde_json::json!({
+                "TypeId": KELVIN.type_id,
+                "State": KELVIN.default_state,
+                "Stats": { "Health": KELVIN.max_health },
+                "Position": { "x": 12.0, "y": 20.0, "z": 32.0 },
+            })
+        );
+    }
+
+    #[test]
+    fn resurrect_missing_actor_without_player() {
+        let mut save = save(vec![], false);
+
+        assert_eq!(KELVIN.resurrect(&mut save), Resurrection::NoActor);
+        assert!(save.actor(KELVIN.type_id).is_none());
+    }
+}
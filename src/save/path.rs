@@ -0,0 +1,387 @@
+//! Paths into a save's document, e.g. `SaveData.VailWorldSim.Actors[?TypeId==9].Stats.Health`.
+
+use std::fmt::Display;
+
+use serde_json::Value;
+
+use super::json;
+
+/// Short names for commonly used paths.
+const ROOT_ALIASES: &[(&str, &str)] = &[
+    ("actor", "SaveData.VailWorldSim.Actors"),
+    ("killstat", "SaveData.VailWorldSim.KillStatsList"),
+    ("PlayerStats", "SaveData.VailWorldSim.PlayerStats"),
+];
+
+/// Short names for commonly used filter keys.
+const KEY_ALIASES: &[(&str, &str)] = &[("type", "TypeId")];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// An object key.
+    Key(String),
+    /// An array index.
+    Index(usize),
+    /// Every element of an array or value of an object.
+    Wildcard,
+    /// Array elements whose value at a (dotted) key compares to a value.
+    Filter(String, Comparison, Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    /// Operators in the order they are matched, so `<=` is found before `<` and `=`.
+    const ALL: [(&'static str, Self); 7] = [
+        ("==", Self::Eq),
+        ("!=", Self::Ne),
+        ("<=", Self::Le),
+        (">=", Self::Ge),
+        ("<", Self::Lt),
+        (">", Self::Gt),
+        ("=", Self::Eq),
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+
+    fn matches(&self, value: &Value, expected: &Value) -> bool {
+        let ordering = match (value.as_f64(), expected.as_f64()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => match (value.as_str(), expected.as_str()) {
+                (Some(a), Some(b)) => Some(a.cmp(b)),
+                _ if value == expected => Some(std::cmp::Ordering::Equal),
+                _ => None,
+            },
+        };
+
+        match (self, ordering) {
+            (Self::Ne, ordering) => ordering != Some(std::cmp::Ordering::Equal),
+            (_, None) => false,
+            (Self::Eq, Some(o)) => o.is_eq(),
+            (Self::Lt, Some(o)) => o.is_lt(),
+            (Self::Le, Some(o)) => o.is_le(),
+            (Self::Gt, Some(o)) => o.is_gt(),
+            (Self::Ge, Some(o)) => o.is_ge(),
+        }
+    }
+}
+
+/// A parsed path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub segments: Vec<Segment>,
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+                Segment::Wildcard => write!(f, "[*]")?,
+                Segment::Filter(key, comparison, value) => {
+                    write!(f, "[?{}{}{}]", key, comparison.as_str(), value)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Path {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+
+        // expand a root alias
+        let root_end = s.find(['.', '[']).unwrap_or(s.len());
+        let expanded;
+        let s = match ROOT_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == &s[..root_end])
+        {
+            Some((_, path)) => {
+                expanded = format!("{}{}", path, &s[root_end..]);
+                expanded.as_str()
+            }
+            None => s,
+        };
+
+        let mut segments = vec![];
+        let mut rest = s;
+
+        while !rest.is_empty() {
+            if let Some(inner) = rest.strip_prefix('[') {
+                let end = inner
+                    .find(']')
+                    .ok_or_else(|| format!("unclosed [ in path {}", s))?;
+                segments.push(parse_selector(&inner[..end])?);
+                rest = &inner[end + 1..];
+            } else {
+                let key_start = rest.strip_prefix('.').unwrap_or(rest);
+                if key_start.len() == rest.len() && !segments.is_empty() {
+                    return Err(format!("expected . or [ in path {}", s));
+                }
+
+                let end = key_start.find(['.', '[']).unwrap_or(key_start.len());
+                let key = &key_start[..end];
+                if key.is_empty() {
+                    return Err(format!("empty key in path {}", s));
+                }
+
+                segments.push(Segment::Key(key.to_owned()));
+                rest = &key_start[end..];
+            }
+        }
+
+        if segments.is_empty() {
+            return Err("empty path".to_owned());
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Find the JSON pointers of every value in the document this path matches.
+    pub fn resolve(&self, document: &Value) -> Vec<String> {
+        self.query(document)
+            .into_iter()
+            .map(|(pointer, _)| pointer)
+            .collect()
+    }
+
+    /// Find every value in the document this path matches, with its JSON pointer.
+    pub fn query<'a>(&self, document: &'a Value) -> Vec<(String, &'a Value)> {
+        let mut matches = vec![(String::new(), document)];
+
+        for segment in self.segments.iter() {
+            matches = matches
+                .into_iter()
+                .flat_map(|(pointer, value)| select(segment, pointer, value))
+                .collect();
+        }
+
+        matches
+    }
+}
+
+fn parse_selector(selector: &str) -> Result<Segment, String> {
+    let selector = selector.trim();
+
+    if let Ok(index) = selector.parse::<usize>() {
+        return Ok(Segment::Index(index));
+    }
+
+    if selector == "*" {
+        return Ok(Segment::Wildcard);
+    }
+
+    // both `[?TypeId==9]` and the shorter `[TypeId=9]`
+    let filter = selector.strip_prefix('?').unwrap_or(selector);
+    let (key, comparison, value) = Comparison::ALL
+        .iter()
+        .filter_map(|(op, comparison)| {
+            filter
+                .find(op)
+                .map(|i| (i, &filter[..i], *comparison, &filter[i + op.len()..]))
+        })
+        .min_by_key(|(i, ..)| *i)
+        .map(|(_, key, comparison, value)| (key, comparison, value))
+        .ok_or_else(|| format!("invalid selector [{}]", selector))?;
+    let key = key.trim();
+    let key = KEY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map_or(key, |(_, key)| key);
+
+    Ok(Segment::Filter(
+        key.to_owned(),
+        comparison,
+        parse_value(value.trim()),
+    ))
+}
+
+/// Parse a literal value, treating anything that isn't valid JSON as a string.
+pub fn parse_value(s: &str) -> Value {
+    serde_json::from_str(s).unwrap_or_else(|_| Value::String(s.to_owned()))
+}
+
+fn select<'a>(segment: &Segment, pointer: String, value: &'a Value) -> Vec<(String, &'a Value)> {
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => map
+            .get(key)
+            .map(|child| (format!("{}/{}", pointer, json::escape(key)), child))
+            .into_iter()
+            .collect(),
+        (Segment::Index(index), Value::Array(array)) => array
+            .get(*index)
+            .map(|child| (format!("{}/{}", pointer, index), child))
+            .into_iter()
+            .collect(),
+        (Segment::Wildcard, Value::Array(array)) => array
+            .iter()
+            .enumerate()
+            .map(|(i, child)| (format!("{}/{}", pointer, i), child))
+            .collect(),
+        (Segment::Wildcard, Value::Object(map)) => map
+            .iter()
+            .map(|(key, child)| (format!("{}/{}", pointer, json::escape(key)), child))
+            .collect(),
+        (Segment::Filter(key, comparison, expected), Value::Array(array)) => array
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| {
+                key.split('.')
+                    .try_fold(*child, |value, key| value.get(key))
+                    .is_some_and(|value| comparison.matches(value, expected))
+            })
+            .map(|(i, child)| (format!("{}/{}", pointer, i), child))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn filter(key: &str, comparison: Comparison, value: Value) -> Segment {
+        Segment::Filter(key.to_owned(), comparison, value)
+    }
+
+    fn key(key: &str) -> Segment {
+        Segment::Key(key.to_owned())
+    }
+
+    #[test]
+    fn aliases_expand() {
+        let path = Path::parse("actor[type=9].Stats.Health").unwrap();
+
+        assert_eq!(
+            path.segments,
+            [
+                key("SaveData"),
+                key("VailWorldSim"),
+                key("Actors"),
+                filter("TypeId", Comparison::Eq, json!(9)),
+                key("Stats"),
+                key("Health"),
+            ]
+        );
+        assert_eq!(
+            path.to_string(),
+            "SaveData.VailWorldSim.Actors[?TypeId==9].Stats.Health"
+        );
+    }
+
+    #[test]
+    fn longest_operator_wins() {
+        let selector = |s| Path::parse(s).unwrap().segments.pop().unwrap();
+
+        assert_eq!(selector("a[?x<=5]"), filter("x", Comparison::Le, json!(5)));
+        assert_eq!(selector("a[?x>=5]"), filter("x", Comparison::Ge, json!(5)));
+        assert_eq!(selector("a[?x<5]"), filter("x", Comparison::Lt, json!(5)));
+        assert_eq!(selector("a[?x==5]"), filter("x", Comparison::Eq, json!(5)));
+        assert_eq!(selector("a[x=5]"), filter("x", Comparison::Eq, json!(5)));
+        assert_eq!(
+            selector("a[?Name!=Kelvin]"),
+            filter("Name", Comparison::Ne, json!("Kelvin"))
+        );
+        // the first operator splits the key from the value, which may hold others
+        assert_eq!(
+            selector("a[Name=a<=b]"),
+            filter("Name", Comparison::Eq, json!("a<=b"))
+        );
+    }
+
+    #[test]
+    fn indices_and_wildcards() {
+        let document = json!({ "a": [{ "b": 1 }, { "b": 2 }], "c": { "x": 3, "y": 4 } });
+        let query = |s| {
+            Path::parse(s)
+                .unwrap()
+                .query(&document)
+                .into_iter()
+                .map(|(pointer, value)| (pointer, value.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(query("a[1].b"), [("/a/1/b".to_owned(), json!(2))]);
+        assert_eq!(
+            query("a[*].b"),
+            [
+                ("/a/0/b".to_owned(), json!(1)),
+                ("/a/1/b".to_owned(), json!(2))
+            ]
+        );
+        assert_eq!(
+            query("c[*]"),
+            [("/c/x".to_owned(), json!(3)), ("/c/y".to_owned(), json!(4))]
+        );
+        assert!(query("a[2].b").is_empty());
+        assert!(query("c[0]").is_empty());
+    }
+
+    #[test]
+    fn filters_compare_nested_keys() {
+        let document = json!({
+            "Actors": [
+                { "TypeId": 9, "Stats": { "Health": 0.0 } },
+                { "TypeId": 10, "Stats": { "Health": 120.0 } },
+                { "TypeId": 11 },
+            ]
+        });
+        let resolve = |s| Path::parse(s).unwrap().resolve(&document);
+
+        assert_eq!(resolve("Actors[?Stats.Health>0]"), ["/Actors/1"]);
+        assert_eq!(
+            resolve("Actors[?Stats.Health<=0].TypeId"),
+            ["/Actors/0/TypeId"]
+        );
+        // an element without the key doesn't match, not even by !=
+        assert_eq!(resolve("Actors[?Stats.Health!=0]"), ["/Actors/1"]);
+        // numbers compare by value, whichever way they are written
+        assert_eq!(resolve("Actors[TypeId=9.0]"), ["/Actors/0"]);
+    }
+
+    #[test]
+    fn pointers_escape_keys() {
+        let document = json!({ "a/b": { "c~d": 1 } });
+
+        assert_eq!(
+            Path::parse("a/b.c~d").unwrap().resolve(&document),
+            ["/a~1b/c~0d"]
+        );
+        assert_eq!(
+            document.pointer("/a~1b/c~0d"),
+            Some(&json!(1)),
+            "pointers resolve back to the value"
+        );
+    }
+
+    #[test]
+    fn invalid_paths() {
+        for path in ["", "a[", "a..b", "a.", "a[b]", "a[0]b"] {
+            assert!(Path::parse(path).is_err(), "{:?} parsed", path);
+        }
+    }
+}
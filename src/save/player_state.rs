@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+use super::Vector3;
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerState {
+    #[serde_as(as = "JsonString")]
+    pub player_state: PlayerStateInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStateInner {
+    #[serde(rename = "_entries")]
+    pub entries: Vec<PlayerStateEntry>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// A single named value in the player state, e.g. `player.position`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerStateEntry {
+    pub name: String,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+impl PlayerStateEntry {
+    pub fn float_value(&self) -> Option<f32> {
+        self.other.get("FloatValue")?.as_f64().map(|f| f as f32)
+    }
+
+    pub fn set_float_value(&mut self, value: f32) {
+        if self.float_value() != Some(value) {
+            self.other.insert("FloatValue".into(), float(value));
+        }
+    }
+
+    pub fn float_array(&self) -> Option<Vec<f32>> {
+        self.other
+            .get("FloatArrayValue")?
+            .as_array()?
+            .iter()
+            .map(|v| v.as_f64().map(|f| f as f32))
+            .collect()
+    }
+
+    /// Set the array, keeping the numbers as written for the values that didn't change.
+    pub fn set_float_array(&mut self, values: &[f32]) {
+        let old = match self.other.get("FloatArrayValue") {
+            Some(Value::Array(old)) => old.clone(),
+            _ => vec![],
+        };
+
+        let values = values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| match old.get(i) {
+                Some(old) if old.as_f64().map(|f| f as f32) == Some(value) => old.clone(),
+                _ => float(value),
+            })
+            .collect();
+        self.other
+            .insert("FloatArrayValue".into(), Value::Array(values));
+    }
+}
+
+/// A float as the game writes it, in its shortest form. Widening it to f64 would add digits
+/// the game never wrote, e.g. 0.1 becoming 0.10000000149011612.
+fn float(value: f32) -> Value {
+    value
+        .to_string()
+        .parse::<f64>()
+        .map_or(Value::Null, Value::from)
+}
+
+impl PlayerStateInner {
+    pub fn entry(&self, name: &str) -> Option<&PlayerStateEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// The player's position in the world.
+    pub fn position(&self) -> Option<Vector3> {
+        match self.entry("player.position")?.float_array()?.as_slice() {
+            [x, y, z, ..] => Some(Vector3::new(*x, *y, *z)),
+            _ => None,
+        }
+    }
+
+    /// Move the player, returning false if the save has no position to change.
+    pub fn set_position(&mut self, position: Vector3) -> bool {
+        let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.name == "player.position")
+        else {
+            return false;
+        };
+
+        match entry.float_array() {
+            Some(mut values) if values.len() >= 3 => {
+                values[..3].copy_from_slice(&[position.x, position.y, position.z]);
+                entry.set_float_array(&values);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(json: Value) -> PlayerStateEntry {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn floats_are_written_as_the_game_writes_them() {
+        let mut position = entry(serde_json::json!({
+            "Name": "player.position",
+            "FloatArrayValue": [1.0, 2.0, 3.0],
+        }));
+        position.set_float_array(&[0.1, 2.0, -251.3584]);
+        assert_eq!(
+            serde_json::to_string(&position.other["FloatArrayValue"]).unwrap(),
+            "[0.1,2.0,-251.3584]"
+        );
+
+        let mut health = entry(serde_json::json!({ "Name": "player.health", "FloatValue": 1.0 }));
+        health.set_float_value(80.7);
+        assert_eq!(
+            serde_json::to_string(&health.other["FloatValue"]).unwrap(),
+            "80.7"
+        );
+    }
+
+    #[test]
+    fn unchanged_floats_keep_their_digits() {
+        // more digits than an f32 holds, as a player position may have
+        let mut position = entry(serde_json::json!({
+            "Name": "player.position",
+            "FloatArrayValue": [10.123456789, 20.0, 30.0],
+        }));
+        position.set_float_array(&[10.123_457, 25.0, 30.0]);
+        assert_eq!(
+            serde_json::to_string(&position.other["FloatArrayValue"]).unwrap(),
+            "[10.123456789,25.0,30.0]"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{json, manage, GenericData, PlayerInventory, PlayerState, SaveType, SelectedSave};
+
+const PLAYER_STATE_FILE: &str = "PlayerStateSaveData.json";
+const PLAYER_INVENTORY_FILE: &str = "PlayerInventorySaveData.json";
+
+/// Folder inside a multiplayer host save holding the data kept for each other player that
+/// has joined the world, in a folder named by their Steam ID.
+pub const PLAYERS_DIR: &str = "Players";
+
+/// The saved state of another player in a multiplayer world, read from the host save.
+#[derive(Debug, Clone)]
+pub struct PlayerData {
+    /// The player's Steam ID.
+    pub steam_id: String,
+
+    pub state: Option<GenericData<PlayerState>>,
+    pub inventory: Option<GenericData<PlayerInventory>>,
+
+    /// The player's own saves of the same world holding an inventory that can be restored.
+    pub snapshots: Vec<(SelectedSave, PathBuf)>,
+}
+
+impl PlayerData {
+    /// Read a player's data from their folder in a host save.
+    pub fn read(save_dir: &Path, path: PathBuf) -> io::Result<Self> {
+        let steam_id = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let state = json::read_optional_file(&path.join(PLAYER_STATE_FILE))?;
+        let inventory = json::read_optional_file(&path.join(PLAYER_INVENTORY_FILE))?;
+
+        // the player's saves on this PC, of this world as far as its game setup tells
+        let host = path.parent().and_then(Path::parent);
+        let snapshots = multiplayer_saves(save_dir)
+            .into_iter()
+            .filter(|(other, other_path)| {
+                other.0 == steam_id
+                    && other_path.join(PLAYER_INVENTORY_FILE).is_file()
+                    && host.is_some_and(|host| manage::same_world(host, other_path))
+            })
+            .collect();
+
+        Ok(Self {
+            steam_id,
+            state,
+            inventory,
+            snapshots,
+        })
+    }
+
+    /// Read every other player the host save at `path` has data for.
+    pub fn find(save_dir: &Path, path: &Path) -> Vec<Self> {
+        let mut players = fs::read_dir(path.join(PLAYERS_DIR))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter(|entry| entry.file_name().to_string_lossy().parse::<u64>().is_ok())
+            .filter_map(|entry| Self::read(save_dir, entry.path()).ok())
+            .collect::<Vec<_>>();
+
+        players.sort_by(|a, b| a.steam_id.cmp(&b.steam_id));
+        players
+    }
+
+    /// Write the player's files into the host save at `path` where they differ from those
+    /// on disk, returning whether any were.
+    pub fn write(&self, path: &Path) -> io::Result<bool> {
+        let dir = path.join(PLAYERS_DIR).join(&self.steam_id);
+        fs::create_dir_all(&dir)?;
+        let mut written = false;
+
+        if let Some(state) = &self.state {
+            written |= write_if_changed(&dir.join(PLAYER_STATE_FILE), state)?;
+        }
+
+        if let Some(inventory) = &self.inventory {
+            written |= write_if_changed(&dir.join(PLAYER_INVENTORY_FILE), inventory)?;
+        }
+
+        Ok(written)
+    }
+
+    /// Replace this player's inventory with the one in another of their saves.
+    pub fn restore_inventory(&mut self, snapshot: &Path) -> io::Result<()> {
+        self.inventory = Some(json::read_file(&snapshot.join(PLAYER_INVENTORY_FILE))?);
+        Ok(())
+    }
+}
+
+/// Write a file unless it already holds the same data, returning whether it was written.
+fn write_if_changed<T: Serialize + DeserializeOwned>(path: &Path, value: &T) -> io::Result<bool> {
+    // compared with nested JSON expanded, as its keys aren't written in a fixed order
+    let expanded = |value: &T| {
+        serde_json::to_value(value).ok().map(|mut value| {
+            json::expand(&mut value);
+            value
+        })
+    };
+    let on_disk = json::read_optional_file::<T>(path)
+        .ok()
+        .flatten()
+        .and_then(|old| expanded(&old));
+
+    if on_disk.is_some() && on_disk == expanded(value) {
+        return Ok(false);
+    }
+
+    json::write_file(path, value)?;
+    Ok(true)
+}
+
+/// Every multiplayer host and client save in the save directory, with its path.
+fn multiplayer_saves(save_dir: &Path) -> Vec<(SelectedSave, PathBuf)> {
+    let mut saves = vec![];
+
+    for steam_id in fs::read_dir(save_dir).into_iter().flatten().flatten() {
+        let steam_id = steam_id.file_name().to_string_lossy().into_owned();
+
+        for save_type in [SaveType::Multiplayer, SaveType::MultiplayerClient] {
+            let type_path = save_dir.join(&steam_id).join(save_type.as_file());
+
+            for save in fs::read_dir(&type_path).into_iter().flatten().flatten() {
+                if save.file_type().is_ok_and(|t| t.is_dir()) {
+                    let name = save.file_name().to_string_lossy().into_owned();
+                    saves.push((
+                        (steam_id.clone(), save_type, name.clone()),
+                        type_path.join(name),
+                    ));
+                }
+            }
+        }
+    }
+
+    saves
+}
@@ -4,6 +4,14 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::{json::JsonString, serde_as};
 
+use super::Vector3;
+
+/// The state of a living actor.
+pub const ACTOR_STATE_ALIVE: u32 = 2;
+
+/// The state of a dead actor.
+pub const ACTOR_STATE_DEAD: u32 = 6;
+
 #[serde_as]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase", default)]
@@ -33,10 +41,30 @@ pub struct Actor {
     pub state: u32,
     pub stats: Option<ActorStats>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Vector3>,
+
     #[serde(flatten)]
     other: HashMap<String, Value>,
 }
 
+impl Actor {
+    /// A living actor at a position with the given health. Only what the save model knows
+    /// is set, anything else the actor needs (such as its outfit) is left to the game.
+    pub fn new(type_id: u32, position: Vector3, health: f32) -> Self {
+        Self {
+            type_id,
+            state: ACTOR_STATE_ALIVE,
+            stats: Some(ActorStats {
+                health,
+                other: HashMap::new(),
+            }),
+            position: Some(position),
+            other: HashMap::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase", default)]
 pub struct ActorStats {
@@ -44,7 +72,7 @@ pub struct ActorStats {
     pub health: f32,
 
     #[serde(flatten)]
-    other: HashMap<String, Value>,
+    pub other: HashMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -67,5 +95,5 @@ pub struct PlayerStats {
     pub last_sighted_time_hours: f32,
 
     #[serde(flatten)]
-    other: HashMap<String, Value>,
+    pub other: HashMap<String, Value>,
 }
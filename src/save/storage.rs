@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+use super::{ItemBlock, Vector3};
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct Storages {
+    #[serde_as(as = "JsonString")]
+    pub storages: StoragesInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct StoragesInner {
+    pub storages: Vec<StorageContainer>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// A storage rack, shelf or chest placed in the world.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct StorageContainer {
+    pub id: u32,
+    pub position: Vector3,
+    pub storages: Vec<StorageSlot>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// A single slot of a storage container.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct StorageSlot {
+    pub item_blocks: Vec<ItemBlock>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
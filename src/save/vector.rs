@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A Unity `Vector3`, as serialized by the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vector3 {
+    #[serde(with = "super::f32_nan")]
+    pub x: f32,
+    #[serde(with = "super::f32_nan")]
+    pub y: f32,
+    #[serde(with = "super::f32_nan")]
+    pub z: f32,
+}
+
+impl Vector3 {
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// The straight-line distance to another point.
+    pub fn distance(&self, other: &Vector3) -> f32 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2) + (self.z - other.z).powi(2))
+            .sqrt()
+    }
+
+    /// Whether no component is NaN or infinite.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+}
+
+impl std::fmt::Display for Vector3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({:.1}, {:.1}, {:.1})", self.x, self.y, self.z)
+    }
+}
+
+impl FromStr for Vector3 {
+    type Err = String;
+
+    /// Parse a point written as `x,y,z`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let components = s
+            .split(',')
+            .map(|c| c.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("invalid point {}: {}", s, e))?;
+
+        match components[..] {
+            [x, y, z] => Ok(Self::new(x, y, z)),
+            _ => Err(format!("invalid point {}, expected x,y,z", s)),
+        }
+    }
+}
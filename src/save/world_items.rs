@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+use super::Vector3;
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct WorldItemManager {
+    #[serde_as(as = "JsonString")]
+    pub world_item_manager: WorldItemManagerInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct WorldItemManagerInner {
+    pub world_items: Vec<WorldItem>,
+
+    /// Records of item pickups placed by the game that have already been collected.
+    pub collected_pickups: Vec<Value>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+/// An item dropped or placed in the world.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct WorldItem {
+    pub item_id: u32,
+    pub position: Vector3,
+    pub state: i32,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{json::JsonString, serde_as};
+
+use super::Vector3;
+
+/// The longest zipline taken to be buildable, in meters. This is an estimate from playing
+/// rather than a limit read from the game, so the zipline tool lets it be changed.
+pub const DEFAULT_MAX_ZIPLINE_LENGTH: f32 = 500.0;
+
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ZipLineManager {
+    #[serde_as(as = "JsonString")]
+    pub zip_line_manager: ZipLineManagerInner,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ZipLineManagerInner {
+    pub zip_lines: Vec<ZipLine>,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ZipLine {
+    pub point_a: Vector3,
+    pub point_b: Vector3,
+
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+impl ZipLine {
+    pub fn length(&self) -> f32 {
+        self.point_a.distance(&self.point_b)
+    }
+
+    /// Whether the zipline can't exist in-game: invalid endpoints, zero length or longer
+    /// than `max_length`.
+    pub fn is_broken(&self, max_length: f32) -> bool {
+        self.problem(max_length).is_some()
+    }
+
+    /// Why the zipline can't exist in-game, if it can't.
+    pub fn problem(&self, max_length: f32) -> Option<String> {
+        if !self.point_a.is_finite() || !self.point_b.is_finite() {
+            Some("an endpoint isn't a valid position".to_owned())
+        } else if self.length() < f32::EPSILON {
+            Some("both endpoints are in the same place".to_owned())
+        } else if self.length() > max_length {
+            Some(format!(
+                "it is {:.0}m long, longer than {}m",
+                self.length(),
+                max_length
+            ))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! The embedded Rhai engine that scripts and plugins run in. Scripts only see a save as its
+//! decoded document, the functions registered here, and can't touch the file system. They
+//! are also limited in how much work they can do, so a runaway script can't hang the editor.
+
+use std::{fmt::Debug, fs, path::Path, sync::Arc};
+
+use parking_lot::Mutex;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST, FLOAT};
+use serde_json::Value;
+
+use crate::save::{
+    json::{self, Change},
+    Save,
+};
+
+/// Variables scripts get besides `save`, as shortcuts to parts of it.
+const SHORTCUTS: &[(&str, &str)] = &[
+    ("actors", "/SaveData/VailWorldSim/Actors"),
+    ("kill_stats", "/SaveData/VailWorldSim/KillStatsList"),
+    ("player_stats", "/SaveData/VailWorldSim/PlayerStats"),
+    ("game_state", "/GameState"),
+];
+
+const MAX_OPERATIONS: u64 = 50_000_000;
+const MAX_CALL_LEVELS: usize = 64;
+const MAX_EXPR_DEPTH: usize = 128;
+
+/// A compiled script and the engine it runs in.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    output: Arc<Mutex<Vec<String>>>,
+}
+
+impl Debug for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Script").finish_non_exhaustive()
+    }
+}
+
+impl Script {
+    pub fn compile(source: &str) -> Result<Self, String> {
+        let output = Arc::new(Mutex::new(vec![]));
+        let engine = engine(output.clone());
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            engine,
+            ast,
+            output,
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let source = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::compile(&source).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Take everything the script printed since the last call.
+    pub fn take_output(&self) -> Vec<String> {
+        std::mem::take(&mut *self.output.lock())
+    }
+
+    /// Whether the script defines a function taking no arguments.
+    pub fn has_fn(&self, name: &str) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|f| f.name == name && f.params.is_empty())
+    }
+
+    /// Call a function taking no arguments and return its result.
+    pub fn eval(&self, name: &str) -> Result<Dynamic, String> {
+        self.engine
+            .call_fn(&mut Scope::new(), &self.ast, name, ())
+            .map_err(error)
+    }
+
+    /// Call a function taking no arguments, with `this` bound to the save's document, and
+    /// return its result. Changes the function makes to `this` are discarded.
+    pub fn eval_on_save(&self, name: &str, save: &Save) -> Result<Dynamic, String> {
+        let mut document = to_dynamic(&save.to_document())?;
+        self.call(name, &mut document)
+    }
+
+    /// Call a function taking no arguments, with `this` bound to the save's document, and
+    /// apply its changes to the save.
+    pub fn call_on_save(&self, name: &str, save: &mut Save) -> Result<Dynamic, String> {
+        let mut document = to_dynamic(&save.to_document())?;
+        let result = self.call(name, &mut document)?;
+        apply(save, &document)?;
+        Ok(result)
+    }
+
+    /// Run the script on a save and return what it changed. Unless this is a dry run, the
+    /// changes are applied to the save.
+    ///
+    /// The script gets the save's document as `save`, parts of it as the [`SHORTCUTS`]
+    /// variables, and the player's position, if known, as `player_position`.
+    pub fn run(&self, save: &mut Save, dry_run: bool) -> Result<Vec<Change>, String> {
+        let before = save.to_document();
+
+        let mut scope = Scope::new();
+        scope.push("save", to_dynamic(&before)?);
+        for (name, pointer) in SHORTCUTS {
+            if let Some(value) = before.pointer(pointer) {
+                scope.push(*name, to_dynamic(value)?);
+            }
+        }
+        if let Some(position) = save.player_position() {
+            scope.push_constant(
+                "player_position",
+                to_dynamic(&serde_json::to_value(position).unwrap())?,
+            );
+        }
+
+        self.engine
+            .run_ast_with_scope(&mut scope, &self.ast)
+            .map_err(error)?;
+
+        let mut document: Value = from_dynamic(
+            &scope
+                .get_value::<Dynamic>("save")
+                .ok_or("the script removed the save variable")?,
+        )?;
+
+        // changed shortcuts are written back into the document
+        for (name, pointer) in SHORTCUTS {
+            let (Some(value), Some(original)) =
+                (scope.get_value::<Dynamic>(name), before.pointer(pointer))
+            else {
+                continue;
+            };
+
+            let value = from_dynamic(&value)?;
+            if json::diff(original, &value).is_empty() {
+                continue;
+            }
+            if let Some(target) = document.pointer_mut(pointer) {
+                *target = value;
+            }
+        }
+
+        let mut changed = save.clone();
+        changed.apply_document(&document)?;
+        let changes = json::diff(&before, &changed.to_document());
+
+        if !dry_run {
+            *save = changed;
+        }
+        Ok(changes)
+    }
+
+    fn call(&self, name: &str, this: &mut Dynamic) -> Result<Dynamic, String> {
+        let options = CallFnOptions::new().bind_this_ptr(this);
+        self.engine
+            .call_fn_with_options(options, &mut Scope::new(), &self.ast, name, ())
+            .map_err(error)
+    }
+}
+
+/// A sandboxed engine sending everything printed to `output`.
+fn engine(output: Arc<Mutex<Vec<String>>>) -> Engine {
+    let mut engine = Engine::new();
+
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH);
+
+    let print_output = output.clone();
+    engine.on_print(move |s| print_output.lock().push(s.to_owned()));
+    engine.on_debug(move |s, _, position| output.lock().push(format!("{} {}", position, s)));
+
+    // the distance between two positions, e.g. of actors
+    engine.register_fn("distance", |a: Map, b: Map| -> FLOAT {
+        let component = |map: &Map, key: &str| -> FLOAT {
+            map.get(key)
+                .and_then(|v| v.as_float().ok().or(v.as_int().ok().map(|i| i as FLOAT)))
+                .unwrap_or(FLOAT::NAN)
+        };
+
+        ["x", "y", "z"]
+            .iter()
+            .map(|key| (component(&a, key) - component(&b, key)).powi(2))
+            .sum::<FLOAT>()
+            .sqrt()
+    });
+
+    engine
+}
+
+fn to_dynamic(document: &Value) -> Result<Dynamic, String> {
+    rhai::serde::to_dynamic(document).map_err(error)
+}
+
+fn from_dynamic(value: &Dynamic) -> Result<Value, String> {
+    rhai::serde::from_dynamic(value).map_err(error)
+}
+
+/// Apply a document changed by a script to the save.
+fn apply(save: &mut Save, document: &Dynamic) -> Result<(), String> {
+    save.apply_document(&from_dynamic(document)?)
+}
+
+fn error(e: Box<EvalAltResult>) -> String {
+    e.to_string()
+}
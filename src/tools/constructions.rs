@@ -0,0 +1,331 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use egui::Button;
+
+use crate::save::{Save, Vector3};
+
+use super::{area_params, render_area, Params, SaveTool};
+
+#[derive(Debug, Clone)]
+pub struct ToolConstructions {
+    center: Vector3,
+    radius: f32,
+    /// What deleting in the radius would remove, awaiting confirmation.
+    pending_delete: Option<Deletion>,
+    status: Option<String>,
+}
+
+/// What deleting in a radius removes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Deletion {
+    pub elements: usize,
+    /// Structures left with no elements, which go too.
+    pub structures: usize,
+    pub screw_structures: usize,
+}
+
+impl Deletion {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for Deletion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} elements, {} empty structures and {} blueprint structures",
+            self.elements, self.structures, self.screw_structures
+        )
+    }
+}
+
+impl SaveTool for ToolConstructions {
+    const NAME: &'static str = "Structures";
+    const DESCRIPTION: &'static str = "Structures the player has built. \
+        Takes delete or repair, with center=x,y,z (defaults to the player) and radius=<meters>.";
+
+    fn new(save: &Save) -> Self {
+        Self {
+            center: save.player_position().unwrap_or_default(),
+            radius: 10.0,
+            pending_delete: None,
+            status: None,
+        }
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        if save.constructions.is_none() && save.screw_structures.is_none() {
+            return None;
+        }
+
+        let (structures, elements, screw_structures) = Self::count(save);
+        Some(format!(
+            "{} structures ({} elements), {} blueprint structures",
+            structures, elements, screw_structures
+        ))
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&["delete", "repair", "center", "radius"])?;
+        if !params.has("delete") && !params.has("repair") {
+            return Err("delete or repair is required".to_owned());
+        }
+
+        let (center, radius) = area_params(save, params)?;
+        let tool = Self {
+            center,
+            radius,
+            pending_delete: None,
+            status: None,
+        };
+
+        let mut done = vec![];
+        if params.has("delete") {
+            let deletion = tool.delete_in_radius(save);
+            done.push(format!("deleted {}", deletion));
+        }
+        if params.has("repair") {
+            let repaired = tool.repair_in_radius(save);
+            done.push(format!("repaired {} structures/elements", repaired));
+        }
+
+        Ok(done)
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        if save.constructions.is_none() && save.screw_structures.is_none() {
+            ui.label("This save has no structures.");
+            return;
+        }
+
+        ui.vertical(|ui| {
+            self.render_lists(save, ui);
+
+            render_area(
+                ui,
+                save.player_position(),
+                &mut self.center,
+                &mut self.radius,
+            );
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        self.pending_delete.is_none(),
+                        Button::new("Delete in radius"),
+                    )
+                    .on_hover_text(
+                        "Remove every element and blueprint structure within the radius, and \
+                        structures left empty. Asks first.",
+                    )
+                    .clicked()
+                {
+                    // worked out on a copy, so the count is exactly what deleting removes
+                    let deletion = self.delete_in_radius(&mut save.clone());
+                    if deletion.is_empty() {
+                        self.status = Some("Nothing is within the radius.".to_owned());
+                    } else {
+                        self.pending_delete = Some(deletion);
+                    }
+                }
+
+                if ui
+                    .add(Button::new("Repair in radius"))
+                    .on_hover_text("Clear the damage on everything within the radius.")
+                    .clicked()
+                {
+                    let repaired = self.repair_in_radius(save);
+                    self.status = Some(format!("Repaired {} structures/elements.", repaired));
+                }
+            });
+
+            if let Some(deletion) = self.pending_delete {
+                ui.label(format!("Delete {}?", deletion));
+                ui.horizontal(|ui| {
+                    if ui.button("Delete").clicked() {
+                        self.pending_delete = None;
+                        let deletion = self.delete_in_radius(save);
+                        self.status = Some(format!("Deleted {}.", deletion));
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_delete = None;
+                    }
+                });
+            }
+
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+    }
+
+    fn save_changed(&mut self, _save: &Save) {
+        // the count may no longer be right
+        self.pending_delete = None;
+    }
+}
+
+impl ToolConstructions {
+    /// Count structures, their elements and blueprint structures.
+    fn count(save: &Save) -> (usize, usize, usize) {
+        let (structures, elements) = save
+            .constructions
+            .as_ref()
+            .map(|c| {
+                let structures = &c.data.constructions.structures;
+                (
+                    structures.len(),
+                    structures.iter().map(|s| s.elements.len()).sum(),
+                )
+            })
+            .unwrap_or_default();
+
+        let screw_structures = save
+            .screw_structures
+            .as_ref()
+            .map_or(0, |s| s.data.screw_structure_instances.structures.len());
+
+        (structures, elements, screw_structures)
+    }
+
+    fn render_lists(&self, save: &Save, ui: &mut egui::Ui) {
+        if let Some(constructions) = &save.constructions {
+            ui.collapsing("Structures", |ui| {
+                egui::ScrollArea::vertical()
+                    .id_source("tool_constructions_structures")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for structure in constructions.data.constructions.structures.iter() {
+                            let mut types = BTreeMap::<u32, usize>::new();
+                            for element in structure.elements.iter() {
+                                *types.entry(element.type_id).or_default() += 1;
+                            }
+
+                            ui.label(format!(
+                                "#{} at {} ({:.0}m away): {} elements",
+                                structure.id,
+                                structure.position,
+                                structure.position.distance(&self.center),
+                                structure.elements.len(),
+                            ))
+                            .on_hover_text(
+                                types
+                                    .iter()
+                                    .map(|(id, count)| format!("type {}: {}", id, count))
+                                    .collect::<Vec<_>>()
+                                    .join("\n"),
+                            );
+                        }
+                    });
+            });
+        }
+
+        if let Some(screw_structures) = &save.screw_structures {
+            ui.collapsing("Blueprint structures", |ui| {
+                let mut types = BTreeMap::<u32, Vec<Vector3>>::new();
+                for structure in screw_structures
+                    .data
+                    .screw_structure_instances
+                    .structures
+                    .iter()
+                {
+                    types.entry(structure.id).or_default().push(structure.pos);
+                }
+
+                egui::ScrollArea::vertical()
+                    .id_source("tool_constructions_screw_structures")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for (id, positions) in types.iter() {
+                            ui.collapsing(format!("Type {} ({})", id, positions.len()), |ui| {
+                                for position in positions.iter() {
+                                    ui.label(format!(
+                                        "{} ({:.0}m away)",
+                                        position,
+                                        position.distance(&self.center)
+                                    ));
+                                }
+                            });
+                        }
+                    });
+            });
+        }
+    }
+
+    /// Delete every element and blueprint structure within the radius. Structures keep
+    /// their elements outside it, and go only once they have none left.
+    pub fn delete_in_radius(&self, save: &mut Save) -> Deletion {
+        let mut deletion = Deletion::default();
+        let in_radius = |p: &Vector3| p.distance(&self.center) <= self.radius;
+
+        if let Some(constructions) = &mut save.constructions {
+            let structures = &mut constructions.data.constructions.structures;
+
+            // structures that had no elements to begin with are only dropped if centered
+            // in the radius
+            let len = structures.len();
+            structures.retain_mut(|structure| {
+                let elements = structure.elements.len();
+                structure.elements.retain(|e| !in_radius(&e.position));
+                deletion.elements += elements - structure.elements.len();
+
+                if elements == 0 {
+                    !in_radius(&structure.position)
+                } else {
+                    !structure.elements.is_empty()
+                }
+            });
+            deletion.structures = len - structures.len();
+        }
+
+        if let Some(screw_structures) = &mut save.screw_structures {
+            let structures = &mut screw_structures.data.screw_structure_instances.structures;
+
+            let len = structures.len();
+            structures.retain(|s| !in_radius(&s.pos));
+            deletion.screw_structures = len - structures.len();
+        }
+
+        deletion
+    }
+
+    /// Clear damage on all structures and elements within the radius, returning how many
+    /// were repaired.
+    pub fn repair_in_radius(&self, save: &mut Save) -> usize {
+        let mut repaired = 0;
+        let in_radius = |p: &Vector3| p.distance(&self.center) <= self.radius;
+
+        let mut repair = |damage: &mut Option<f32>| {
+            if matches!(damage, Some(d) if *d != 0.0) {
+                *damage = Some(0.0);
+                repaired += 1;
+            }
+        };
+
+        if let Some(constructions) = &mut save.constructions {
+            for structure in constructions.data.constructions.structures.iter_mut() {
+                for element in structure.elements.iter_mut() {
+                    if in_radius(&element.position) {
+                        repair(&mut element.damage);
+                    }
+                }
+            }
+        }
+
+        if let Some(screw_structures) = &mut save.screw_structures {
+            for structure in screw_structures
+                .data
+                .screw_structure_instances
+                .structures
+                .iter_mut()
+            {
+                if in_radius(&structure.pos) {
+                    repair(&mut structure.damage);
+                }
+            }
+        }
+
+        repaired
+    }
+}
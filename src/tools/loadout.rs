@@ -0,0 +1,403 @@
+use egui::DragValue;
+
+use crate::{
+    save::{item_info, item_name, ArmourPiece, ItemKind, Save, ARMOUR_SLOTS, ITEMS},
+    validate::Issue,
+};
+
+use super::{Params, SaveTool};
+
+const CLOTHING_PATH: &str = "PlayerClothingSystem.PlayerClothingSystem.Clothing";
+const ARMOUR_PATH: &str = "PlayerArmourSystem.PlayerArmourSystem.ArmourPieces";
+
+#[derive(Debug, Clone)]
+pub struct ToolLoadout {
+    equip_clothing: Option<u32>,
+    equip_armour: Option<u32>,
+    /// Problems with the loadout as of the save's last change.
+    issues: Vec<Issue>,
+}
+
+impl SaveTool for ToolLoadout {
+    const NAME: &'static str = "Loadout";
+    const DESCRIPTION: &'static str = "Clothing and armour the player is wearing. Takes \
+        equip-clothing=<id>, unequip-clothing=<id>, equip-armour=<id> with an optional \
+        slot=<slot>, unequip-armour=<slot> and repair-armour.";
+
+    fn new(save: &Save) -> Self {
+        Self {
+            equip_clothing: None,
+            equip_armour: None,
+            issues: Self::validate(save),
+        }
+    }
+
+    fn save_changed(&mut self, save: &Save) {
+        self.issues = Self::validate(save);
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        if save.clothing.is_none() && save.armour.is_none() {
+            ui.label("This save has no player loadout.");
+            return;
+        }
+
+        ui.vertical(|ui| {
+            if let Some(clothing) = &mut save.clothing {
+                let clothing = &mut clothing.data.player_clothing_system.clothing;
+
+                ui.label("Clothing");
+                let mut unequip = None;
+                for (i, &item_id) in clothing.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(item_name(item_id));
+                        if ui.small_button("Unequip").clicked() {
+                            unequip = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = unequip {
+                    clothing.remove(i);
+                }
+
+                ui.horizontal(|ui| {
+                    item_picker(
+                        ui,
+                        "tool_loadout_clothing",
+                        &mut self.equip_clothing,
+                        |kind| kind == ItemKind::Clothing,
+                    );
+                    if let Some(item_id) = self.equip_clothing {
+                        if ui.button("Equip").clicked() {
+                            Self::equip_clothing(clothing, item_id);
+                        }
+                    }
+                });
+            }
+
+            if let Some(armour) = &mut save.armour {
+                let pieces = &mut armour.data.player_armour_system.armour_pieces;
+
+                ui.label("Armour");
+                item_picker(ui, "tool_loadout_armour", &mut self.equip_armour, |kind| {
+                    matches!(kind, ItemKind::Armour { .. })
+                });
+
+                egui::Grid::new("tool_loadout_armour_slots")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for slot in 0..ARMOUR_SLOTS {
+                            ui.label(format!("Slot {}", slot + 1));
+                            ui.horizontal(|ui| match pieces.iter().position(|p| p.slot == slot) {
+                                Some(i) => {
+                                    let piece = &mut pieces[i];
+                                    ui.label(item_name(piece.item_id));
+                                    ui.add(
+                                        DragValue::new(&mut piece.remaining_armour_points)
+                                            .clamp_range(0.0..=f32::MAX)
+                                            .prefix("durability: "),
+                                    );
+                                    if max_durability(piece.item_id).is_some()
+                                        && ui.small_button("Repair").clicked()
+                                    {
+                                        Self::repair_armour(piece);
+                                    }
+                                    if ui.small_button("Unequip").clicked() {
+                                        pieces.remove(i);
+                                    }
+                                }
+                                None => {
+                                    ui.weak("Empty");
+                                    if let Some(item_id) = self.equip_armour {
+                                        if ui.small_button("Equip").clicked() {
+                                            Self::equip_armour(pieces, item_id, slot);
+                                        }
+                                    }
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            for issue in self.issues.iter() {
+                ui.colored_label(ui.visuals().warn_fg_color, &issue.message);
+            }
+        });
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&[
+            "equip-clothing",
+            "unequip-clothing",
+            "equip-armour",
+            "slot",
+            "unequip-armour",
+            "repair-armour",
+        ])?;
+
+        let equip_clothing = params.get::<u32>("equip-clothing")?;
+        let unequip_clothing = params.get::<u32>("unequip-clothing")?;
+        let equip_armour = params.get::<u32>("equip-armour")?;
+        let unequip_armour = params.get::<u32>("unequip-armour")?;
+        // slots are numbered from 1 here, as they are in the editor
+        let slot = params.get::<u32>("slot")?;
+        if equip_clothing.is_none()
+            && unequip_clothing.is_none()
+            && equip_armour.is_none()
+            && unequip_armour.is_none()
+            && !params.has("repair-armour")
+        {
+            return Err(
+                "equip-clothing, unequip-clothing, equip-armour, unequip-armour or \
+                repair-armour is required"
+                    .to_owned(),
+            );
+        }
+        for slot in [slot, unequip_armour].into_iter().flatten() {
+            if !(1..=ARMOUR_SLOTS).contains(&slot) {
+                return Err(format!("slot must be from 1 to {}", ARMOUR_SLOTS));
+            }
+        }
+        let is_clothing = |kind| kind == ItemKind::Clothing;
+        let is_armour = |kind| matches!(kind, ItemKind::Armour { .. });
+        for (key, item_id, fits) in [
+            (
+                "equip-clothing",
+                equip_clothing,
+                &is_clothing as &dyn Fn(ItemKind) -> bool,
+            ),
+            ("equip-armour", equip_armour, &is_armour),
+        ] {
+            if let Some(item_id) = item_id {
+                match item_info(item_id) {
+                    None => return Err(format!("{}: unknown item {}", key, item_id)),
+                    Some(info) if !fits(info.kind) => {
+                        return Err(format!("{}: {} can't be worn there", key, info.name))
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let mut done = vec![];
+
+        if equip_clothing.is_some() || unequip_clothing.is_some() {
+            let Some(clothing) = &mut save.clothing else {
+                return Err("save has no player clothing".to_owned());
+            };
+            let clothing = &mut clothing.data.player_clothing_system.clothing;
+
+            if let Some(item_id) = unequip_clothing {
+                let len = clothing.len();
+                clothing.retain(|&c| c != item_id);
+                done.push(match len - clothing.len() {
+                    0 => format!("{} isn't worn", item_name(item_id)),
+                    _ => format!("unequipped {}", item_name(item_id)),
+                });
+            }
+            if let Some(item_id) = equip_clothing {
+                done.push(match Self::equip_clothing(clothing, item_id) {
+                    true => format!("equipped {}", item_name(item_id)),
+                    false => format!("{} is already worn", item_name(item_id)),
+                });
+            }
+        }
+
+        if equip_armour.is_some() || unequip_armour.is_some() || params.has("repair-armour") {
+            let Some(armour) = &mut save.armour else {
+                return Err("save has no player armour".to_owned());
+            };
+            let pieces = &mut armour.data.player_armour_system.armour_pieces;
+
+            if let Some(slot) = unequip_armour {
+                match pieces.iter().position(|p| p.slot == slot - 1) {
+                    Some(i) => {
+                        let piece = pieces.remove(i);
+                        done.push(format!(
+                            "unequipped {} from slot {}",
+                            item_name(piece.item_id),
+                            slot
+                        ));
+                    }
+                    None => done.push(format!("slot {} is empty", slot)),
+                }
+            }
+            if let Some(item_id) = equip_armour {
+                let free = (0..ARMOUR_SLOTS).find(|&s| pieces.iter().all(|p| p.slot != s));
+                let slot = match slot {
+                    Some(slot) if pieces.iter().any(|p| p.slot == slot - 1) => {
+                        return Err(format!("slot {} is already in use", slot))
+                    }
+                    Some(slot) => slot - 1,
+                    None => free.ok_or("every armour slot is in use")?,
+                };
+                Self::equip_armour(pieces, item_id, slot);
+                done.push(format!(
+                    "equipped {} in slot {}",
+                    item_name(item_id),
+                    slot + 1
+                ));
+            }
+            if params.has("repair-armour") {
+                let repaired = pieces
+                    .iter_mut()
+                    .map(Self::repair_armour)
+                    .filter(|&repaired| repaired)
+                    .count();
+                done.push(format!("repaired {} armour pieces", repaired));
+            }
+        }
+
+        Ok(done)
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        let clothing = save
+            .clothing
+            .as_ref()
+            .map(|c| c.data.player_clothing_system.clothing.len());
+        let armour = save
+            .armour
+            .as_ref()
+            .map(|a| a.data.player_armour_system.armour_pieces.len());
+
+        match (clothing, armour) {
+            (None, None) => None,
+            (clothing, armour) => Some(format!(
+                "{} clothing, {} armour pieces",
+                clothing.unwrap_or_default(),
+                armour.unwrap_or_default()
+            )),
+        }
+    }
+
+    /// Check the equipped clothing and armour against the item catalog.
+    fn validate(save: &Save) -> Vec<Issue> {
+        let mut problems = vec![];
+
+        if let Some(clothing) = &save.clothing {
+            for (i, &item_id) in clothing
+                .data
+                .player_clothing_system
+                .clothing
+                .iter()
+                .enumerate()
+            {
+                let path = format!("{}[{}]", CLOTHING_PATH, i);
+                match item_info(item_id) {
+                    None => problems.push(Issue::warning(
+                        path,
+                        format!("unknown clothing item {}", item_id),
+                    )),
+                    Some(info) if info.kind != ItemKind::Clothing => problems.push(Issue::warning(
+                        path,
+                        format!("{} is worn as clothing", info.name),
+                    )),
+                    _ => (),
+                }
+            }
+        }
+
+        if let Some(armour) = &save.armour {
+            let pieces = &armour.data.player_armour_system.armour_pieces;
+
+            for (i, piece) in pieces.iter().enumerate() {
+                let name = item_name(piece.item_id);
+                let warning = |message| Issue::warning(format!("{}[{}]", ARMOUR_PATH, i), message);
+
+                match item_info(piece.item_id) {
+                    None => {
+                        problems.push(warning(format!("unknown armour item {}", piece.item_id)))
+                    }
+                    Some(info) if !matches!(info.kind, ItemKind::Armour { .. }) => {
+                        problems.push(warning(format!("{} is equipped as armour", name)))
+                    }
+                    _ => (),
+                }
+
+                if piece.slot >= ARMOUR_SLOTS {
+                    problems.push(warning(format!(
+                        "{} is in invalid slot {}",
+                        name,
+                        piece.slot + 1
+                    )));
+                } else if pieces[..i].iter().any(|p| p.slot == piece.slot) {
+                    problems.push(warning(format!(
+                        "slot {} has more than one piece",
+                        piece.slot + 1
+                    )));
+                }
+
+                if let Some(durability) = max_durability(piece.item_id) {
+                    if !(0.0..=durability).contains(&piece.remaining_armour_points) {
+                        problems.push(warning(format!(
+                            "{} in slot {} has durability {} (max {})",
+                            name,
+                            piece.slot + 1,
+                            piece.remaining_armour_points,
+                            durability
+                        )));
+                    }
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+impl ToolLoadout {
+    /// Put on a piece of clothing, returning false if it's already worn.
+    fn equip_clothing(clothing: &mut Vec<u32>, item_id: u32) -> bool {
+        if clothing.contains(&item_id) {
+            return false;
+        }
+        clothing.push(item_id);
+        true
+    }
+
+    /// Put a fresh armour piece in a 0-based slot.
+    fn equip_armour(pieces: &mut Vec<ArmourPiece>, item_id: u32, slot: u32) {
+        pieces.push(ArmourPiece::new(
+            item_id,
+            slot,
+            max_durability(item_id).unwrap_or_default(),
+        ));
+    }
+
+    /// Restore an armour piece to full durability, returning whether it changed.
+    fn repair_armour(piece: &mut ArmourPiece) -> bool {
+        match max_durability(piece.item_id) {
+            Some(durability) if piece.remaining_armour_points != durability => {
+                piece.remaining_armour_points = durability;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The durability of a fresh armour piece, if the item is known armour.
+fn max_durability(item_id: u32) -> Option<f32> {
+    match item_info(item_id)?.kind {
+        ItemKind::Armour { durability } => Some(durability),
+        _ => None,
+    }
+}
+
+/// Render a combo box of catalog items of the matching kind.
+fn item_picker(
+    ui: &mut egui::Ui,
+    id: &str,
+    selected: &mut Option<u32>,
+    filter: impl Fn(ItemKind) -> bool,
+) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(selected.map_or_else(|| "Select item".to_owned(), item_name))
+        .show_ui(ui, |ui| {
+            for item in ITEMS.iter().filter(|i| filter(i.kind)) {
+                ui.selectable_value(selected, Some(item.id), item.name);
+            }
+        });
+}
@@ -0,0 +1,579 @@
+use std::{env, fs::File, io::BufReader, path::Path};
+
+use egui::{
+    Color32, ColorImage, DragValue, Pos2, Rect, Response, Sense, Stroke, TextureHandle, Vec2,
+};
+use png::{ColorType, Transformations};
+
+use crate::save::{item_name, npc, Save, Vector3, ACTOR_STATE_DEAD};
+
+use super::SaveTool;
+
+/// Half the width of the area the map shows, in meters. The island fits within this distance
+/// of the origin on both the x and z axes.
+const MAP_EXTENT: f32 = 2048.0;
+/// The map image, looked for next to the executable. It should cover the whole map area,
+/// with north (positive z) up.
+const MAP_IMAGE: &str = "map.png";
+const MAP_SIZE: f32 = 512.0;
+const MAX_ZOOM: f32 = 64.0;
+/// How close to a marker the pointer must be to pick it, in points.
+const PICK_DISTANCE: f32 = 8.0;
+/// How far the pointer must move before a marker is dragged, in points, so clicking a marker
+/// to select it doesn't nudge it.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+const PLAYER_COLOR: Color32 = Color32::from_rgb(80, 160, 255);
+const COMPANION_COLOR: Color32 = Color32::from_rgb(80, 220, 80);
+const ENEMY_COLOR: Color32 = Color32::from_rgb(230, 60, 60);
+const DEAD_COLOR: Color32 = Color32::GRAY;
+const STRUCTURE_COLOR: Color32 = Color32::from_rgb(200, 140, 60);
+const ITEM_COLOR: Color32 = Color32::from_rgb(230, 210, 80);
+
+/// Something plotted on the map, by its index in the save.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Marker {
+    Player,
+    Actor(usize),
+    Structure(usize),
+    ScrewStructure(usize),
+    WorldItem(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolMap {
+    image: MapImage,
+    /// The world x and z shown at the center of the map.
+    center: Vec2,
+    zoom: f32,
+    show_actors: bool,
+    show_structures: bool,
+    show_items: bool,
+    selected: Option<Marker>,
+    /// The marker being dragged, if a drag started on one, and where it was.
+    dragging: Option<(Marker, Vector3)>,
+    /// The last marker moved by dragging, and where it was before.
+    last_move: Option<(Marker, Vector3)>,
+}
+
+#[derive(Clone)]
+enum MapImage {
+    Missing,
+    Failed(String),
+    Decoded(ColorImage),
+    Loaded(TextureHandle),
+}
+
+impl std::fmt::Debug for MapImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => f.write_str("Missing"),
+            Self::Failed(e) => f.debug_tuple("Failed").field(e).finish(),
+            Self::Decoded(image) => f.debug_tuple("Decoded").field(&image.size).finish(),
+            Self::Loaded(texture) => f.debug_tuple("Loaded").field(&texture.id()).finish(),
+        }
+    }
+}
+
+impl SaveTool for ToolMap {
+    const NAME: &'static str = "Map";
+    const DESCRIPTION: &'static str = "Where the player, actors, structures and items are. \
+        Click a marker to select it and drag it to move it.";
+
+    fn new(_save: &Save) -> Self {
+        let image = match env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.parent()?.join(MAP_IMAGE)))
+            .filter(|path| path.is_file())
+        {
+            Some(path) => match read_png(&path) {
+                Ok(image) => MapImage::Decoded(image),
+                Err(e) => MapImage::Failed(format!("{}: {}", path.display(), e)),
+            },
+            None => MapImage::Missing,
+        };
+
+        Self {
+            image,
+            center: Vec2::ZERO,
+            zoom: 1.0,
+            show_actors: true,
+            show_structures: true,
+            show_items: false,
+            selected: None,
+            dragging: None,
+            last_move: None,
+        }
+    }
+
+    fn save_changed(&mut self, save: &Save) {
+        // markers are indices into the save, which other tools may have removed
+        if self.selected.is_some_and(|m| position(save, m).is_none()) {
+            self.selected = None;
+        }
+        if self
+            .last_move
+            .is_some_and(|(m, _)| position(save, m).is_none())
+        {
+            self.last_move = None;
+        }
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show_actors, "Actors");
+                ui.checkbox(&mut self.show_structures, "Structures");
+                ui.checkbox(&mut self.show_items, "Items");
+                if ui.button("Reset view").clicked() {
+                    self.center = Vec2::ZERO;
+                    self.zoom = 1.0;
+                }
+                if let Some((marker, from)) = self.last_move {
+                    if ui.button("Undo move").clicked() {
+                        set_position(save, marker, from);
+                        self.selected = Some(marker);
+                        self.last_move = None;
+                    }
+                }
+            });
+
+            match &self.image {
+                MapImage::Missing => {
+                    ui.weak(format!(
+                        "Put a {} next to the program to show it under the map.",
+                        MAP_IMAGE
+                    ));
+                }
+                MapImage::Failed(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+                _ => (),
+            }
+
+            let (rect, response) =
+                ui.allocate_exact_size(Vec2::splat(MAP_SIZE), Sense::click_and_drag());
+            let markers = self.markers(save);
+
+            self.handle_input(save, ui, rect, &response, &markers);
+            self.paint(ui, rect, save, &markers);
+
+            if let Some(hovered) = response
+                .hover_pos()
+                .and_then(|pointer| self.pick(rect, pointer, &markers))
+            {
+                let height = position(save, hovered).map_or(0.0, |p| p.y);
+                response.on_hover_text_at_pointer(format!(
+                    "{}, y: {:.1}",
+                    name(save, hovered),
+                    height
+                ));
+            }
+
+            self.render_selected(save, ui);
+        });
+    }
+}
+
+impl ToolMap {
+    /// Every marker shown, with its position.
+    fn markers(&self, save: &Save) -> Vec<(Marker, Vector3)> {
+        let mut markers = vec![];
+
+        if self.show_structures {
+            if let Some(constructions) = &save.constructions {
+                let structures = &constructions.data.constructions.structures;
+                markers.extend(
+                    structures
+                        .iter()
+                        .enumerate()
+                        .map(|(i, s)| (Marker::Structure(i), s.position)),
+                );
+            }
+
+            if let Some(screw_structures) = &save.screw_structures {
+                let structures = &screw_structures.data.screw_structure_instances.structures;
+                markers.extend(
+                    structures
+                        .iter()
+                        .enumerate()
+                        .map(|(i, s)| (Marker::ScrewStructure(i), s.pos)),
+                );
+            }
+        }
+
+        if self.show_items {
+            if let Some(world_items) = &save.world_items {
+                let items = &world_items.data.world_item_manager.world_items;
+                markers.extend(
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| (Marker::WorldItem(i), item.position)),
+                );
+            }
+        }
+
+        if self.show_actors {
+            let actors = &save.save_data.data.vail_world_sim.actors;
+            markers.extend(
+                actors
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, actor)| Some((Marker::Actor(i), actor.position?))),
+            );
+        }
+
+        // the player is drawn last, on top of everything
+        if let Some(position) = save.player_position() {
+            markers.push((Marker::Player, position));
+        }
+
+        markers.retain(|(_, position)| position.is_finite());
+        markers
+    }
+
+    /// How many points a meter takes up on screen.
+    fn scale(&self) -> f32 {
+        MAP_SIZE / (MAP_EXTENT * 2.0) * self.zoom
+    }
+
+    fn to_screen(&self, rect: Rect, position: Vector3) -> Pos2 {
+        rect.center()
+            + Vec2::new(position.x - self.center.x, self.center.y - position.z) * self.scale()
+    }
+
+    /// The world x and z under a point on screen.
+    fn to_world(&self, rect: Rect, pos: Pos2) -> Vec2 {
+        let offset = (pos - rect.center()) / self.scale();
+        Vec2::new(self.center.x + offset.x, self.center.y - offset.y)
+    }
+
+    /// The topmost marker near a point on screen.
+    fn pick(&self, rect: Rect, pos: Pos2, markers: &[(Marker, Vector3)]) -> Option<Marker> {
+        markers
+            .iter()
+            .rev()
+            .find(|(_, position)| self.to_screen(rect, *position).distance(pos) <= PICK_DISTANCE)
+            .map(|(marker, _)| *marker)
+    }
+
+    fn handle_input(
+        &mut self,
+        save: &mut Save,
+        ui: &mut egui::Ui,
+        rect: Rect,
+        response: &Response,
+        markers: &[(Marker, Vector3)],
+    ) {
+        if response.hovered() {
+            // take the scroll so the editor doesn't scroll as well
+            let scroll = ui.input_mut(|i| std::mem::take(&mut i.scroll_delta).y);
+            if scroll != 0.0 {
+                self.zoom = (self.zoom * (scroll * 0.005).exp()).clamp(1.0, MAX_ZOOM);
+            }
+        }
+
+        let pointer = response.interact_pointer_pos();
+
+        if response.clicked() {
+            self.selected = pointer.and_then(|pos| self.pick(rect, pos, markers));
+        }
+
+        if response.drag_started() {
+            let marker = pointer.and_then(|pos| self.pick(rect, pos, markers));
+            self.dragging = marker.and_then(|m| Some((m, position(save, m)?)));
+            if marker.is_some() {
+                self.selected = marker;
+            }
+        }
+
+        if response.dragged() {
+            let origin = ui.input(|i| i.pointer.press_origin());
+            match (self.dragging, pointer) {
+                (Some((marker, from)), Some(pos)) => {
+                    // markers only move across the map, keeping their height
+                    if origin.map_or(true, |origin| origin.distance(pos) > DRAG_THRESHOLD) {
+                        let to = self.to_world(rect, pos);
+                        set_position(save, marker, Vector3::new(to.x, from.y, to.y));
+                    }
+                }
+                _ => {
+                    let delta = response.drag_delta() / self.scale();
+                    self.center -= Vec2::new(delta.x, -delta.y);
+                }
+            }
+        }
+
+        if response.drag_released() {
+            if let Some((marker, from)) = self.dragging.take() {
+                if position(save, marker) != Some(from) {
+                    self.last_move = Some((marker, from));
+                }
+            }
+        }
+    }
+
+    fn paint(&mut self, ui: &egui::Ui, rect: Rect, save: &Save, markers: &[(Marker, Vector3)]) {
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+        if let MapImage::Decoded(image) = &self.image {
+            let texture =
+                ui.ctx()
+                    .load_texture("tool_map_image", image.clone(), Default::default());
+            self.image = MapImage::Loaded(texture);
+        }
+        if let MapImage::Loaded(texture) = &self.image {
+            let map = Rect::from_two_pos(
+                self.to_screen(rect, Vector3::new(-MAP_EXTENT, 0.0, MAP_EXTENT)),
+                self.to_screen(rect, Vector3::new(MAP_EXTENT, 0.0, -MAP_EXTENT)),
+            );
+            let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+            painter.image(texture.id(), map, uv, Color32::WHITE);
+        }
+
+        for (marker, position) in markers.iter() {
+            let pos = self.to_screen(rect, *position);
+            let radius = match marker {
+                Marker::Player => 6.0,
+                Marker::Actor(_) => 4.0,
+                _ => 3.0,
+            };
+
+            painter.circle_filled(pos, radius, color(save, *marker));
+            if self.selected == Some(*marker) {
+                painter.circle_stroke(pos, radius + 3.0, Stroke::new(2.0, Color32::WHITE));
+            }
+        }
+
+        painter.rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+    }
+
+    /// Show the selected marker's name and position, which can be edited.
+    fn render_selected(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        let Some(marker) = self.selected else {
+            ui.weak("Click a marker to select it.");
+            return;
+        };
+        let Some(mut position) = position(save, marker) else {
+            self.selected = None;
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(name(save, marker));
+
+            let mut changed = false;
+            changed |= ui
+                .add(DragValue::new(&mut position.x).prefix("x: "))
+                .changed();
+            // dragging on the map keeps the height, which may need setting here afterwards
+            changed |= ui
+                .add(DragValue::new(&mut position.y).prefix("y: "))
+                .on_hover_text("Height. Dragging on the map keeps it as it was.")
+                .changed();
+            changed |= ui
+                .add(DragValue::new(&mut position.z).prefix("z: "))
+                .changed();
+            if changed {
+                set_position(save, marker, position);
+            }
+
+            if ui.small_button("Center").clicked() {
+                self.center = Vec2::new(position.x, position.z);
+            }
+            if ui.small_button("Deselect").clicked() {
+                self.selected = None;
+            }
+        });
+    }
+}
+
+fn position(save: &Save, marker: Marker) -> Option<Vector3> {
+    match marker {
+        Marker::Player => save.player_position(),
+        Marker::Actor(i) => save.save_data.data.vail_world_sim.actors.get(i)?.position,
+        Marker::Structure(i) => Some(
+            save.constructions
+                .as_ref()?
+                .data
+                .constructions
+                .structures
+                .get(i)?
+                .position,
+        ),
+        Marker::ScrewStructure(i) => Some(
+            save.screw_structures
+                .as_ref()?
+                .data
+                .screw_structure_instances
+                .structures
+                .get(i)?
+                .pos,
+        ),
+        Marker::WorldItem(i) => Some(
+            save.world_items
+                .as_ref()?
+                .data
+                .world_item_manager
+                .world_items
+                .get(i)?
+                .position,
+        ),
+    }
+}
+
+fn set_position(save: &mut Save, marker: Marker, position: Vector3) {
+    match marker {
+        Marker::Player => {
+            save.set_player_position(position);
+        }
+        Marker::Actor(i) => {
+            if let Some(actor) = save.save_data.data.vail_world_sim.actors.get_mut(i) {
+                actor.position = Some(position);
+            }
+        }
+        Marker::Structure(i) => {
+            let Some(structure) = save
+                .constructions
+                .as_mut()
+                .and_then(|c| c.data.constructions.structures.get_mut(i))
+            else {
+                return;
+            };
+
+            // a structure's elements move with it
+            let offset = Vector3::new(
+                position.x - structure.position.x,
+                position.y - structure.position.y,
+                position.z - structure.position.z,
+            );
+            structure.position = position;
+            for element in structure.elements.iter_mut() {
+                element.position = Vector3::new(
+                    element.position.x + offset.x,
+                    element.position.y + offset.y,
+                    element.position.z + offset.z,
+                );
+            }
+        }
+        Marker::ScrewStructure(i) => {
+            if let Some(structure) = save
+                .screw_structures
+                .as_mut()
+                .and_then(|s| s.data.screw_structure_instances.structures.get_mut(i))
+            {
+                structure.pos = position;
+            }
+        }
+        Marker::WorldItem(i) => {
+            if let Some(item) = save
+                .world_items
+                .as_mut()
+                .and_then(|w| w.data.world_item_manager.world_items.get_mut(i))
+            {
+                item.position = position;
+            }
+        }
+    }
+}
+
+fn name(save: &Save, marker: Marker) -> String {
+    match marker {
+        Marker::Player => "Player".to_owned(),
+        Marker::Actor(i) => {
+            let actor = &save.save_data.data.vail_world_sim.actors[i];
+            let name = npc(actor.type_id)
+                .map(|npc| npc.name.to_owned())
+                .unwrap_or_else(|| format!("Actor type {}", actor.type_id));
+
+            if actor.state == ACTOR_STATE_DEAD {
+                format!("{} (dead)", name)
+            } else {
+                name
+            }
+        }
+        Marker::Structure(i) => {
+            let structure = &save
+                .constructions
+                .as_ref()
+                .unwrap()
+                .data
+                .constructions
+                .structures[i];
+            format!(
+                "Structure #{} ({} elements)",
+                structure.id,
+                structure.elements.len()
+            )
+        }
+        Marker::ScrewStructure(i) => {
+            let structure = &save
+                .screw_structures
+                .as_ref()
+                .unwrap()
+                .data
+                .screw_structure_instances
+                .structures[i];
+            format!("Blueprint structure type {}", structure.id)
+        }
+        Marker::WorldItem(i) => {
+            let item = &save
+                .world_items
+                .as_ref()
+                .unwrap()
+                .data
+                .world_item_manager
+                .world_items[i];
+            item_name(item.item_id)
+        }
+    }
+}
+
+fn color(save: &Save, marker: Marker) -> Color32 {
+    match marker {
+        Marker::Player => PLAYER_COLOR,
+        Marker::Actor(i) => {
+            let actor = &save.save_data.data.vail_world_sim.actors[i];
+            if actor.state == ACTOR_STATE_DEAD {
+                DEAD_COLOR
+            } else if npc(actor.type_id).is_some() {
+                COMPANION_COLOR
+            } else {
+                ENEMY_COLOR
+            }
+        }
+        Marker::Structure(_) | Marker::ScrewStructure(_) => STRUCTURE_COLOR,
+        Marker::WorldItem(_) => ITEM_COLOR,
+    }
+}
+
+/// Read a PNG image, in any of its 8 or 16 bit color types.
+fn read_png(path: &Path) -> Result<ColorImage, String> {
+    let mut decoder =
+        png::Decoder::new(BufReader::new(File::open(path).map_err(|e| e.to_string())?));
+    decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
+
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+    let pixels = &buf[..info.buffer_size()];
+    let size = [info.width as usize, info.height as usize];
+
+    let rgba = match info.color_type {
+        ColorType::Rgba => pixels.to_vec(),
+        ColorType::Rgb => pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        ColorType::GrayscaleAlpha => pixels
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        ColorType::Grayscale => pixels.iter().flat_map(|&p| [p, p, p, 255]).collect(),
+        ColorType::Indexed => return Err("unsupported indexed color image".to_owned()),
+    };
+
+    Ok(ColorImage::from_rgba_unmultiplied(size, &rgba))
+}
@@ -1,13 +1,159 @@
-mod kelvin;
-mod virginia;
+mod constructions;
+mod loadout;
+mod map;
+mod npcs;
+mod player_stats;
+mod players;
+mod plugins;
+mod query;
+mod raw_json;
+mod repair;
+mod script;
+mod storage;
+mod validate;
+mod world_items;
+mod ziplines;
 
-use self::{kelvin::ToolKelvin, virginia::ToolVirginia};
-use crate::save::Save;
-use std::fmt::Debug;
+pub use self::{
+    constructions::ToolConstructions, loadout::ToolLoadout, map::ToolMap, npcs::ToolNpcs,
+    player_stats::ToolPlayerStats, players::ToolPlayers, plugins::ToolPlugins, query::ToolQuery,
+    raw_json::ToolRawJson, repair::ToolRepair, script::ToolScript, storage::ToolStorage,
+    validate::ToolValidate, world_items::ToolWorldItems, ziplines::ToolZipLines,
+};
+use crate::{
+    save::{Save, Vector3},
+    validate::Issue,
+};
+use egui::DragValue;
+use std::{collections::BTreeMap, fmt::Debug, str::FromStr};
 
 pub trait SaveTool: Debug + Clone {
+    /// The tool's name in the editor.
+    const NAME: &'static str;
+    /// What the tool does, shown on hover and by the `tools` command.
+    const DESCRIPTION: &'static str;
+
     fn new(save: &Save) -> Self;
     fn render(&mut self, save: &mut Save, ui: &mut egui::Ui);
+
+    /// Called when the save has changed since the tools were last rendered, whether by this
+    /// tool, another tool or anything else. Tools caching anything worked out from the save
+    /// must refresh it here.
+    fn save_changed(&mut self, _save: &Save) {}
+
+    /// A short summary of the tool's part of the save. The editor only works it out again when
+    /// the save changes.
+    fn status(_save: &Save) -> Option<String> {
+        None
+    }
+
+    /// The status after [`SaveTool::save_changed`], for tools that can work it out from what
+    /// they cached rather than the save.
+    fn current_status(&self, save: &Save) -> Option<String> {
+        Self::status(save)
+    }
+
+    /// Apply the tool without the editor, describing what was done.
+    fn apply(_save: &mut Save, _params: &Params) -> Result<Vec<String>, String> {
+        Err(format!("{} can only be used from the editor", Self::NAME))
+    }
+
+    /// Problems in the tool's part of the save.
+    fn validate(_save: &Save) -> Vec<Issue> {
+        vec![]
+    }
+}
+
+/// A tool's metadata and UI-free entry points, for using it from the command line.
+#[derive(Debug)]
+pub struct ToolInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub status: fn(&Save) -> Option<String>,
+    pub apply: fn(&mut Save, &Params) -> Result<Vec<String>, String>,
+    pub validate: fn(&Save) -> Vec<Issue>,
+}
+
+impl ToolInfo {
+    /// The name used to pick the tool from the command line, e.g. `player-stats`.
+    pub fn id(&self) -> String {
+        self.name.to_lowercase().replace(' ', "-")
+    }
+}
+
+/// Find a tool by its id.
+pub fn tool(id: &str) -> Option<&'static ToolInfo> {
+    TOOLS.iter().find(|tool| tool.id() == id.to_lowercase())
+}
+
+/// Problems found by every tool.
+pub fn validate(save: &Save) -> Vec<Issue> {
+    TOOLS
+        .iter()
+        .flat_map(|tool| (tool.validate)(save))
+        .collect()
+}
+
+/// Parameters for applying a tool without the editor, given as `key=value`, or just `key`
+/// for a flag.
+#[derive(Debug, Clone, Default)]
+pub struct Params(BTreeMap<String, String>);
+
+impl Params {
+    pub fn parse<S: AsRef<str>>(params: &[S]) -> Self {
+        Self(
+            params
+                .iter()
+                .map(|param| match param.as_ref().split_once('=') {
+                    Some((key, value)) => (key.trim().to_owned(), value.trim().to_owned()),
+                    None => (param.as_ref().trim().to_owned(), String::new()),
+                })
+                .collect(),
+        )
+    }
+
+    /// Fail on any parameter the tool doesn't know.
+    pub fn expect(&self, known: &[&str]) -> Result<(), String> {
+        match self.0.keys().find(|key| !known.contains(&key.as_str())) {
+            Some(key) => Err(format!(
+                "unknown parameter {}, expected {}",
+                key,
+                known.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// The parsed value of a parameter, if given.
+    pub fn get<T>(&self, key: &str) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.0
+            .get(key)
+            .map(|value| value.parse().map_err(|e| format!("{}: {}", key, e)))
+            .transpose()
+    }
+}
+
+impl std::fmt::Display for Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(":")?;
+            }
+            f.write_str(key)?;
+            if !value.is_empty() {
+                write!(f, "={}", value)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 macro_rules! save_tools {
@@ -15,19 +161,139 @@ macro_rules! save_tools {
         #[derive(Debug, Clone)]
         pub struct SaveTools {
             $(pub $name: $type,)+
+            /// The save's fingerprint when it was last checked for changes.
+            fingerprint: u64,
+            /// Each tool's status, in order, as of the last change to the save.
+            statuses: Vec<Option<String>>,
         }
 
         impl SaveTools {
             pub fn new(save: &Save) -> Self {
                 Self {
                     $($name: <$type>::new(save),)+
+                    fingerprint: save.fingerprint(),
+                    statuses: vec![$(<$type>::status(save),)+],
                 }
             }
+
+            /// Render a grid row for each tool, skipping the hidden ones.
+            pub fn render(&mut self, save: &mut Save, ui: &mut egui::Ui, hidden: &[&str]) {
+                let mut statuses = self.statuses.iter();
+                $(
+                    let status = statuses.next().and_then(Option::as_ref);
+                    if !hidden.contains(&<$type>::NAME) {
+                        ui.vertical(|ui| {
+                            ui.label(<$type>::NAME).on_hover_text(<$type>::DESCRIPTION);
+                            if let Some(status) = status {
+                                ui.weak(status);
+                            }
+                        });
+                        self.$name.render(save, ui);
+                        ui.end_row();
+                    }
+                )+
+
+                // the save is only edited in response to input, so hashing it is skipped on
+                // frames without any, such as those drawn while the pointer is just moving,
+                // and until a drag is over, which may edit the save every frame
+                let settled = ui.input(|i| {
+                    !i.pointer.any_down()
+                        && i.events.iter().any(|e| !matches!(e, egui::Event::PointerMoved(_)))
+                });
+                if settled && self.check_changed(save) {
+                    // show what the tools worked out from the change
+                    ui.ctx().request_repaint();
+                }
+            }
+
+            /// Let every tool catch up with changes to the save since it was last checked,
+            /// returning whether there were any.
+            fn check_changed(&mut self, save: &Save) -> bool {
+                let fingerprint = save.fingerprint();
+                if fingerprint == self.fingerprint {
+                    return false;
+                }
+
+                self.fingerprint = fingerprint;
+                $(self.$name.save_changed(save);)+
+                self.statuses = vec![$(self.$name.current_status(save),)+];
+                true
+            }
         }
+
+        pub const TOOLS: &[ToolInfo] = &[
+            $(ToolInfo {
+                name: <$type>::NAME,
+                description: <$type>::DESCRIPTION,
+                status: <$type>::status,
+                apply: <$type>::apply,
+                validate: <$type>::validate,
+            },)+
+        ];
     }
 }
 
 save_tools!(
-    kelvin => ToolKelvin,
-    virginia => ToolVirginia,
+    npcs => ToolNpcs,
+    player_stats => ToolPlayerStats,
+    loadout => ToolLoadout,
+    players => ToolPlayers,
+    constructions => ToolConstructions,
+    storage => ToolStorage,
+    world_items => ToolWorldItems,
+    zip_lines => ToolZipLines,
+    map => ToolMap,
+    validate => ToolValidate,
+    repair => ToolRepair,
+    query => ToolQuery,
+    script => ToolScript,
+    plugins => ToolPlugins,
+    raw_json => ToolRawJson,
 );
+
+/// Render controls for picking a spherical area of the world, centered on a point.
+fn render_area(
+    ui: &mut egui::Ui,
+    player_position: Option<Vector3>,
+    center: &mut Vector3,
+    radius: &mut f32,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Center");
+        ui.add(DragValue::new(&mut center.x).prefix("x: "));
+        ui.add(DragValue::new(&mut center.y).prefix("y: "));
+        ui.add(DragValue::new(&mut center.z).prefix("z: "));
+
+        if let Some(position) = player_position {
+            if ui.button("Player").clicked() {
+                *center = position;
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Radius");
+        ui.add(
+            DragValue::new(radius)
+                .clamp_range(0.0..=10000.0)
+                .suffix("m"),
+        );
+    });
+}
+
+/// The area picked by `center=x,y,z` and `radius=<meters>` parameters. The center defaults to
+/// the player's position.
+fn area_params(save: &Save, params: &Params) -> Result<(Vector3, f32), String> {
+    let center = match params.get::<Vector3>("center")? {
+        Some(center) => center,
+        None => save
+            .player_position()
+            .ok_or("center=x,y,z is required, the player's position is unknown")?,
+    };
+    let radius = params
+        .get::<f32>("radius")?
+        .filter(|r| *r >= 0.0)
+        .ok_or("radius=<meters> is required")?;
+
+    Ok((center, radius))
+}
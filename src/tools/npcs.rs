@@ -0,0 +1,153 @@
+use egui::Button;
+
+use crate::{
+    save::{NpcDefinition, Resurrection, Save, ACTOR_STATE_DEAD, NPCS},
+    validate::{Issue, ACTORS_PATH, GAME_STATE_PATH},
+};
+
+use super::{Params, SaveTool};
+
+#[derive(Debug, Clone)]
+pub struct ToolNpcs {
+    npcs: Vec<ToolNpc>,
+}
+
+/// The state of the tool for one NPC.
+#[derive(Debug, Clone)]
+struct ToolNpc {
+    npc: &'static NpcDefinition,
+    resurrection: Option<Resurrection>,
+}
+
+impl SaveTool for ToolNpcs {
+    const NAME: &'static str = "Companions";
+    const DESCRIPTION: &'static str = "Resurrect Kelvin and Virginia. \
+        Takes resurrect=<name>, or resurrect=all.";
+
+    fn new(_save: &Save) -> Self {
+        Self {
+            npcs: NPCS
+                .iter()
+                .map(|npc| ToolNpc {
+                    npc,
+                    resurrection: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        egui::Grid::new("tool_npcs").num_columns(2).show(ui, |ui| {
+            for tool in self.npcs.iter_mut() {
+                let is_dead = tool.npc.is_dead(save);
+                let is_missing = save.actor(tool.npc.type_id).is_none();
+
+                ui.label(tool.npc.name);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(is_dead || is_missing, Button::new("Resurrect"))
+                        .clicked()
+                    {
+                        tool.resurrection = Some(tool.npc.resurrect(save));
+                    }
+
+                    if let Some(resurrection) = tool.resurrection {
+                        ui.label(format!("Resurrected, {}.", resurrection));
+                    } else if is_missing {
+                        ui.label(format!("{} has no actor in the world.", tool.npc.name));
+                    } else if !is_dead {
+                        ui.label(format!("{} is not dead.", tool.npc.name));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    }
+
+    fn save_changed(&mut self, save: &Save) {
+        // a resurrection is old news once the NPC has died again or lost their actor
+        for tool in self.npcs.iter_mut() {
+            if tool.npc.is_dead(save) || save.actor(tool.npc.type_id).is_none() {
+                tool.resurrection = None;
+            }
+        }
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        Some(
+            NPCS.iter()
+                .map(|npc| {
+                    let status = if npc.is_dead(save) {
+                        "dead"
+                    } else if save.actor(npc.type_id).is_none() {
+                        "missing"
+                    } else {
+                        "alive"
+                    };
+                    format!("{} {}", npc.name, status)
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&["resurrect"])?;
+
+        let name = params
+            .get::<String>("resurrect")?
+            .ok_or("resurrect=<name> is required")?;
+        let npcs = if name.eq_ignore_ascii_case("all") {
+            NPCS.iter().collect()
+        } else {
+            vec![NPCS
+                .iter()
+                .find(|npc| npc.name.eq_ignore_ascii_case(&name))
+                .ok_or_else(|| format!("unknown NPC: {}", name))?]
+        };
+
+        Ok(npcs
+            .into_iter()
+            .map(|npc| format!("resurrected {} ({})", npc.name, npc.resurrect(save)))
+            .collect())
+    }
+
+    fn validate(save: &Save) -> Vec<Issue> {
+        let mut issues = vec![];
+        let state = &save.game_state.data.game_state;
+        let actors = &save.save_data.data.vail_world_sim.actors;
+
+        for npc in NPCS {
+            let count = actors.iter().filter(|a| a.type_id == npc.type_id).count();
+            if count > 1 {
+                issues.push(Issue::error(
+                    format!("{}[?TypeId=={}]", ACTORS_PATH, npc.type_id),
+                    format!(
+                        "{} has {} actors, there should only be one",
+                        npc.name, count
+                    ),
+                ));
+            }
+
+            let Some(actor) = save.actor(npc.type_id) else {
+                continue;
+            };
+
+            let is_dead = (npc.is_flagged_dead)(state);
+            if is_dead != (actor.state == ACTOR_STATE_DEAD) {
+                issues.push(Issue::error(
+                    format!("{}[?TypeId=={}].State", ACTORS_PATH, npc.type_id),
+                    format!(
+                        "is {} but {} is flagged {} in {}",
+                        actor.state,
+                        npc.name,
+                        if is_dead { "dead" } else { "alive" },
+                        GAME_STATE_PATH
+                    ),
+                ));
+            }
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,192 @@
+use egui::{Button, DragValue};
+use serde_json::Value;
+
+use crate::{
+    save::Save,
+    validate::{Issue, PLAYER_STATS_PATH},
+};
+
+use super::{Params, SaveTool};
+
+/// Descriptions of the stats tracked in `PlayerStats`, shown on hover.
+const CUT_TREES_HELP: &str = "Trees felled by the player. Cannibals react to \
+    deforestation, so a high count makes them visit the player's base more often.";
+const SEEN_IN_VILLAGE_HELP: &str = "Times the player has been spotted inside a cannibal \
+    village. Cannibal aggression escalates with this count; resetting it to zero \
+    restarts the escalation.";
+const LAST_SIGHTED_HELP: &str = "In-game hour at which cannibals last sighted the player. \
+    Unset means the player has not been sighted yet.";
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolPlayerStats;
+
+impl SaveTool for ToolPlayerStats {
+    const NAME: &'static str = "Player stats";
+    const DESCRIPTION: &'static str = "Counters the game keeps about the player. \
+        Takes cut-trees=<count>, seen-in-village=<count> and last-sighted=<hours|unset>.";
+
+    fn new(_save: &Save) -> Self {
+        Self
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        let stats = &mut save.save_data.data.vail_world_sim.player_stats;
+
+        egui::Grid::new("tool_player_stats")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Cut trees").on_hover_text(CUT_TREES_HELP);
+                ui.add(DragValue::new(&mut stats.cut_trees).clamp_range(0..=i32::MAX));
+                ui.end_row();
+
+                ui.label("Seen in village")
+                    .on_hover_text(SEEN_IN_VILLAGE_HELP);
+                ui.horizontal(|ui| {
+                    ui.add(
+                        DragValue::new(&mut stats.seen_in_village_count).clamp_range(0..=i32::MAX),
+                    );
+
+                    if ui
+                        .add_enabled(stats.seen_in_village_count != 0, Button::new("Reset"))
+                        .on_hover_text("Restart cannibal aggression escalation.")
+                        .clicked()
+                    {
+                        stats.seen_in_village_count = 0;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Last sighted (hours)")
+                    .on_hover_text(LAST_SIGHTED_HELP);
+                ui.horizontal(|ui| {
+                    if stats.last_sighted_time_hours.is_nan() {
+                        ui.label("Unset");
+                        if ui.button("Set").clicked() {
+                            stats.last_sighted_time_hours = 0.0;
+                        }
+                    } else {
+                        ui.add(
+                            DragValue::new(&mut stats.last_sighted_time_hours)
+                                .clamp_range(0.0..=f32::MAX),
+                        );
+                        if ui.button("Unset").clicked() {
+                            stats.last_sighted_time_hours = f32::NAN;
+                        }
+                    }
+                });
+                ui.end_row();
+
+                // any other numeric counters the game stores, which are shown by key alone
+                // as what they affect isn't known
+                let mut keys = stats.other.keys().cloned().collect::<Vec<_>>();
+                keys.sort();
+
+                for key in keys {
+                    let value = stats.other.get_mut(&key).unwrap();
+                    if let Some(new_value) = render_number(ui, &key, value) {
+                        *value = new_value;
+                    }
+                }
+            });
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        let stats = &save.save_data.data.vail_world_sim.player_stats;
+        Some(format!(
+            "{} trees cut, seen in village {} times",
+            stats.cut_trees, stats.seen_in_village_count
+        ))
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&["cut-trees", "seen-in-village", "last-sighted"])?;
+        let stats = &mut save.save_data.data.vail_world_sim.player_stats;
+        let mut done = vec![];
+
+        if let Some(count) = count(params, "cut-trees")? {
+            stats.cut_trees = count;
+            done.push(format!("set cut trees to {}", stats.cut_trees));
+        }
+
+        if let Some(count) = count(params, "seen-in-village")? {
+            stats.seen_in_village_count = count;
+            done.push(format!(
+                "set seen in village count to {}",
+                stats.seen_in_village_count
+            ));
+        }
+
+        match params.get::<String>("last-sighted")?.as_deref() {
+            Some("unset") => {
+                stats.last_sighted_time_hours = f32::NAN;
+                done.push("unset last sighted time".to_owned());
+            }
+            Some(hours) => {
+                stats.last_sighted_time_hours = hours
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|h| *h >= 0.0)
+                    .ok_or_else(|| format!("last-sighted: invalid hours {}", hours))?;
+                done.push(format!("set last sighted time to {}", hours));
+            }
+            None => (),
+        }
+
+        Ok(done)
+    }
+
+    fn validate(save: &Save) -> Vec<Issue> {
+        let mut issues = vec![];
+        let stats = &save.save_data.data.vail_world_sim.player_stats;
+
+        for (key, value) in [
+            ("CutTrees", stats.cut_trees),
+            ("SeenInVillageCount", stats.seen_in_village_count),
+        ] {
+            if value < 0 {
+                issues.push(Issue::error(
+                    format!("{}.{}", PLAYER_STATS_PATH, key),
+                    format!("count {} is negative", value),
+                ));
+            }
+        }
+
+        if stats.last_sighted_time_hours < 0.0 {
+            issues.push(Issue::warning(
+                format!("{}.LastSightedTimeHours", PLAYER_STATS_PATH),
+                format!("{} is negative", stats.last_sighted_time_hours),
+            ));
+        }
+
+        issues
+    }
+}
+
+/// A count parameter, which can't be negative.
+fn count(params: &Params, key: &str) -> Result<Option<i32>, String> {
+    match params.get::<i32>(key)? {
+        Some(count) if count < 0 => Err(format!("{}: count {} is negative", key, count)),
+        count => Ok(count),
+    }
+}
+
+/// Render a row for a JSON number, returning the new value if it was changed.
+fn render_number(ui: &mut egui::Ui, key: &str, value: &Value) -> Option<Value> {
+    let mut changed = None;
+
+    if let Some(mut n) = value.as_i64() {
+        ui.label(key);
+        if ui.add(DragValue::new(&mut n)).changed() {
+            changed = Some(Value::from(n));
+        }
+        ui.end_row();
+    } else if let Some(mut n) = value.as_f64() {
+        ui.label(key);
+        if ui.add(DragValue::new(&mut n).speed(0.1)).changed() {
+            changed = Some(Value::from(n));
+        }
+        ui.end_row();
+    }
+
+    changed
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use egui::{Button, DragValue};
+
+use crate::save::{item_name, PlayerData, Save};
+
+use super::SaveTool;
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolPlayers {
+    /// The snapshot picked to restore from, per player Steam ID.
+    restore_from: HashMap<String, usize>,
+    status: Option<String>,
+}
+
+impl SaveTool for ToolPlayers {
+    const NAME: &'static str = "Other players";
+    const DESCRIPTION: &'static str =
+        "Inventories and states of the other players a multiplayer host save keeps.";
+
+    fn new(_save: &Save) -> Self {
+        Self::default()
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        (!save.players.is_empty()).then(|| format!("{} players", save.players.len()))
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        if save.players.is_empty() {
+            ui.label("No other players have data for this world.");
+            return;
+        }
+
+        let own_inventory = save.player_inventory.clone();
+
+        ui.vertical(|ui| {
+            for player in save.players.iter_mut() {
+                let steam_id = player.steam_id.clone();
+
+                ui.push_id(&steam_id, |ui| {
+                    ui.collapsing(&steam_id, |ui| {
+                        Self::render_state(player, ui);
+                        Self::render_inventory(player, ui);
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(
+                                    own_inventory.is_some(),
+                                    Button::new("Copy this save's inventory"),
+                                )
+                                .on_hover_text(
+                                    "Give this player a copy of the loaded save's inventory.",
+                                )
+                                .clicked()
+                            {
+                                player.inventory = own_inventory.clone();
+                                self.status = Some(format!("Copied inventory to {}.", steam_id));
+                            }
+                        });
+
+                        if player.snapshots.is_empty() {
+                            ui.weak("This player has no saves of this world to restore from.");
+                        } else {
+                            let selected = self.restore_from.entry(steam_id.clone()).or_default();
+
+                            ui.horizontal(|ui| {
+                                ui.label("Their save:");
+                                egui::ComboBox::from_id_source("restore_from")
+                                    .selected_text(snapshot_name(player, *selected))
+                                    .show_ui(ui, |ui| {
+                                        for i in 0..player.snapshots.len() {
+                                            ui.selectable_value(
+                                                selected,
+                                                i,
+                                                snapshot_name(player, i),
+                                            );
+                                        }
+                                    });
+
+                                if ui
+                                    .button("Restore inventory")
+                                    .on_hover_text(
+                                        "Replace this player's inventory with the one in their \
+                                        own save of this world.",
+                                    )
+                                    .clicked()
+                                {
+                                    let path = player.snapshots[*selected].1.clone();
+                                    self.status = Some(match player.restore_inventory(&path) {
+                                        Ok(_) => format!(
+                                            "Restored inventory for {} from {}.",
+                                            steam_id,
+                                            snapshot_name(player, *selected)
+                                        ),
+                                        Err(e) => format!("Error: {}", e),
+                                    });
+                                }
+                            });
+                        }
+                    });
+                });
+            }
+
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+    }
+}
+
+impl ToolPlayers {
+    fn render_state(player: &mut PlayerData, ui: &mut egui::Ui) {
+        let state = match &mut player.state {
+            Some(state) => &mut state.data.player_state,
+            None => {
+                ui.label("No saved player state.");
+                return;
+            }
+        };
+
+        egui::Grid::new("state").num_columns(2).show(ui, |ui| {
+            for entry in state.entries.iter_mut() {
+                if let Some(mut value) = entry.float_value() {
+                    ui.label(&entry.name);
+                    if ui.add(DragValue::new(&mut value).speed(0.1)).changed() {
+                        entry.set_float_value(value);
+                    }
+                    ui.end_row();
+                }
+            }
+        });
+    }
+
+    fn render_inventory(player: &mut PlayerData, ui: &mut egui::Ui) {
+        let blocks = match &mut player.inventory {
+            Some(inventory) => {
+                &mut inventory
+                    .data
+                    .player_inventory
+                    .item_instance_manager_data
+                    .item_blocks
+            }
+            None => {
+                ui.label("No saved inventory.");
+                return;
+            }
+        };
+
+        ui.collapsing(format!("Inventory ({} stacks)", blocks.len()), |ui| {
+            let mut remove = None;
+
+            for (i, block) in blocks.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(item_name(block.item_id));
+                    ui.add(DragValue::new(&mut block.total_count));
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+
+            if let Some(i) = remove {
+                blocks.remove(i);
+            }
+        });
+    }
+}
+
+fn snapshot_name(player: &PlayerData, i: usize) -> String {
+    let ((_, save_type, name), _) = &player.snapshots[i];
+    format!("{} save {}", save_type, name)
+}
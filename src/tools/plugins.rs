@@ -0,0 +1,227 @@
+use std::{env, fs, path::PathBuf, sync::Arc};
+
+use egui::Button;
+use rhai::{Dynamic, Map};
+
+use crate::{save::Save, script::Script};
+
+use super::SaveTool;
+
+/// The folder plugins are loaded from, next to the executable.
+const PLUGINS_DIR: &str = "plugins";
+
+#[derive(Debug, Clone)]
+pub struct ToolPlugins {
+    plugins: Vec<Plugin>,
+    /// Plugins that failed to load.
+    errors: Vec<String>,
+}
+
+/// A tool provided by a Rhai script. See the README for what a plugin can define.
+#[derive(Debug, Clone)]
+struct Plugin {
+    name: String,
+    description: Option<String>,
+    script: Arc<Script>,
+    /// The widgets of the plugin's panel, built again whenever the save changes.
+    panel: Option<Result<Vec<Widget>, String>>,
+    status: Option<Result<String, String>>,
+}
+
+#[derive(Debug, Clone)]
+enum Widget {
+    Label(String),
+    Button {
+        text: String,
+        action: String,
+        enabled: bool,
+    },
+}
+
+impl SaveTool for ToolPlugins {
+    const NAME: &'static str = "Plugins";
+    const DESCRIPTION: &'static str = "Tools added as Rhai scripts in the plugins folder.";
+
+    fn new(_save: &Save) -> Self {
+        let mut plugins = vec![];
+        let mut errors = vec![];
+
+        for path in plugin_paths() {
+            match Plugin::load(path) {
+                Ok(plugin) => plugins.push(plugin),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        plugins.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { plugins, errors }
+    }
+
+    fn save_changed(&mut self, _save: &Save) {
+        for plugin in self.plugins.iter_mut() {
+            plugin.panel = None;
+        }
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            if self.plugins.is_empty() && self.errors.is_empty() {
+                ui.label(format!(
+                    "No plugins found. Put .rhai scripts in a \"{}\" folder next to the \
+                    program.",
+                    PLUGINS_DIR
+                ));
+            }
+
+            for error in self.errors.iter() {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+
+            egui::Grid::new("tool_plugins")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for plugin in self.plugins.iter_mut() {
+                        let label = ui.label(&plugin.name);
+                        if let Some(description) = &plugin.description {
+                            label.on_hover_text(description);
+                        }
+
+                        plugin.render(save, ui);
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
+
+impl Plugin {
+    fn load(path: PathBuf) -> Result<Self, String> {
+        let script = Script::read(&path)?;
+        let text = |name: &str| {
+            script
+                .has_fn(name)
+                .then(|| script.eval(name).ok())
+                .flatten()
+                .map(|v| v.to_string())
+        };
+
+        let name = text("name").unwrap_or_else(|| {
+            path.file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        });
+        let description = text("description");
+
+        Ok(Self {
+            name,
+            description,
+            script: Arc::new(script),
+            panel: None,
+            status: None,
+        })
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        let script = self.script.clone();
+        let panel = self.panel.get_or_insert_with(|| {
+            if !script.has_fn("panel") {
+                return Err("The plugin has no panel function.".to_owned());
+            }
+            script.eval_on_save("panel", save).and_then(widgets)
+        });
+
+        let mut action = None;
+
+        ui.vertical(|ui| {
+            match panel {
+                Ok(widgets) => {
+                    for widget in widgets.iter() {
+                        match widget {
+                            Widget::Label(text) => {
+                                ui.label(text);
+                            }
+                            Widget::Button {
+                                text,
+                                action: name,
+                                enabled,
+                            } => {
+                                if ui.add_enabled(*enabled, Button::new(text)).clicked() {
+                                    action = Some(name.clone());
+                                }
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e.as_str());
+                }
+            }
+
+            if let Some(status) = &self.status {
+                match status {
+                    Ok(status) => ui.label(status),
+                    Err(e) => ui.colored_label(ui.visuals().error_fg_color, e),
+                };
+            }
+        });
+
+        if let Some(action) = action {
+            self.status = Some(
+                script
+                    .call_on_save(&action, save)
+                    .map(|_| script.take_output().join("\n")),
+            );
+        }
+    }
+}
+
+/// Every plugin script in the plugins folder.
+fn plugin_paths() -> Vec<PathBuf> {
+    let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(PLUGINS_DIR)))
+    else {
+        return vec![];
+    };
+
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "rhai"))
+        .collect()
+}
+
+/// Read the widgets returned by a plugin's `panel` function: an array of maps, each either
+/// `#{ label: "text" }` or `#{ button: "text", action: "function", enabled: true }`.
+fn widgets(panel: Dynamic) -> Result<Vec<Widget>, String> {
+    let text = |map: &Map, key: &str| map.get(key).map(|v| v.to_string());
+
+    panel
+        .into_array()
+        .map_err(|t| format!("panel should return an array, not {}", t))?
+        .into_iter()
+        .map(|widget| {
+            let map = widget
+                .try_cast::<Map>()
+                .ok_or("panel widgets should be maps")?;
+
+            if let Some(label) = text(&map, "label") {
+                Ok(Widget::Label(label))
+            } else if let Some(button) = text(&map, "button") {
+                Ok(Widget::Button {
+                    text: button,
+                    action: text(&map, "action").ok_or("a button has no action")?,
+                    enabled: map
+                        .get("enabled")
+                        .and_then(|e| e.as_bool().ok())
+                        .unwrap_or(true),
+                })
+            } else {
+                Err("panel widgets should have a label or button".to_owned())
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,95 @@
+use egui::{RichText, ScrollArea, TextEdit};
+
+use crate::save::{path::Path, Save};
+
+use super::SaveTool;
+
+#[derive(Debug, Clone)]
+pub struct ToolQuery {
+    query: String,
+    /// The last query run, run again whenever the save changes.
+    ran: Option<Path>,
+    /// Pointers and pretty printed values of the last query's matches.
+    results: Result<Vec<(String, String)>, String>,
+}
+
+impl SaveTool for ToolQuery {
+    const NAME: &'static str = "Query";
+    const DESCRIPTION: &'static str = "Find values in the save by path.";
+
+    fn new(_save: &Save) -> Self {
+        Self {
+            query: "actor[?TypeId==9].Stats".to_owned(),
+            ran: None,
+            results: Ok(vec![]),
+        }
+    }
+
+    fn save_changed(&mut self, save: &Save) {
+        if let Some(path) = &self.ran {
+            self.results = Ok(Self::run(path, save));
+        }
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    TextEdit::singleline(&mut self.query)
+                        .code_editor()
+                        .hint_text("SaveData.VailWorldSim.Actors[?TypeId==9].Stats"),
+                );
+
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button("Run").clicked() || submitted {
+                    match Path::parse(&self.query) {
+                        Ok(path) => {
+                            self.results = Ok(Self::run(&path, save));
+                            self.ran = Some(path);
+                        }
+                        Err(e) => {
+                            self.results = Err(e);
+                            self.ran = None;
+                        }
+                    }
+                }
+            })
+            .response
+            .on_hover_text(
+                "Paths start at a file, e.g. GameState.IsRobbyDead. actor, killstat and \
+                PlayerStats are shortcuts. [?Key==value] filters arrays (also !=, <, <=, >, >=) \
+                and [*] selects every element.",
+            );
+
+            match &self.results {
+                Ok(results) => {
+                    ui.label(format!("{} matches", results.len()));
+
+                    ScrollArea::vertical()
+                        .id_source("tool_query_results")
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            for (pointer, value) in results.iter() {
+                                ui.label(RichText::new(pointer).strong());
+                                ui.label(RichText::new(value).monospace());
+                            }
+                        });
+                }
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+            }
+        });
+    }
+}
+
+impl ToolQuery {
+    /// Query the save, returning the pointers and pretty printed values of the matches.
+    fn run(path: &Path, save: &Save) -> Vec<(String, String)> {
+        path.query(&save.to_document())
+            .into_iter()
+            .map(|(pointer, value)| (pointer, serde_json::to_string_pretty(value).unwrap()))
+            .collect()
+    }
+}
@@ -0,0 +1,207 @@
+use egui::{CollapsingHeader, DragValue, RichText, TextEdit};
+use serde_json::Value;
+
+use crate::save::{json, Save};
+
+use super::SaveTool;
+
+#[derive(Debug, Clone)]
+pub struct ToolRawJson {
+    /// The save's document being edited, loaded when first shown.
+    document: Option<Value>,
+    /// The document as it was loaded, to tell which values were edited.
+    original: Option<Value>,
+    changed: bool,
+    /// Whether the save changed elsewhere while this has unapplied edits.
+    outdated: bool,
+    status: Option<Result<String, String>>,
+}
+
+impl SaveTool for ToolRawJson {
+    const NAME: &'static str = "Raw JSON";
+    const DESCRIPTION: &'static str = "Edit anything in the save from a tree of its raw contents.";
+
+    fn new(_save: &Save) -> Self {
+        Self {
+            document: None,
+            original: None,
+            changed: false,
+            outdated: false,
+            status: None,
+        }
+    }
+
+    fn save_changed(&mut self, _save: &Save) {
+        if self.changed {
+            self.outdated = true;
+        } else {
+            self.document = None;
+            self.original = None;
+        }
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(self.changed, egui::Button::new("Apply"))
+                    .on_hover_text(
+                        "Apply the edits to the save. Click \"Save changes\" to write it.",
+                    )
+                    .clicked()
+                {
+                    if let (Some(original), Some(document)) = (&self.original, &self.document) {
+                        self.status = Some(Self::apply_edits(save, original, document));
+                        self.changed = false;
+                        self.outdated = false;
+                    }
+                }
+
+                if ui
+                    .button("Reload")
+                    .on_hover_text("Discard edits and show the save's current contents.")
+                    .clicked()
+                {
+                    self.document = None;
+                    self.original = None;
+                    self.changed = false;
+                    self.outdated = false;
+                    self.status = None;
+                }
+            });
+
+            if self.outdated {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "The save has changed since these edits were made. Applying only changes \
+                    the values edited here, reload to see the rest.",
+                );
+            }
+
+            match &self.status {
+                Some(Ok(status)) => {
+                    ui.label(status);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+                None => (),
+            }
+
+            let document = self.document.get_or_insert_with(|| {
+                let document = save.to_document();
+                self.original = Some(document.clone());
+                document
+            });
+            if let Value::Object(files) = document {
+                for (name, value) in files.iter_mut() {
+                    self.changed |=
+                        render_value(ui, name, value, format!("/{}", json::escape(name)));
+                }
+            }
+        });
+    }
+}
+
+impl ToolRawJson {
+    /// Apply the values edited in a document to the save as it is now, leaving anything
+    /// changed elsewhere since the document was loaded alone.
+    fn apply_edits(save: &mut Save, original: &Value, edited: &Value) -> Result<String, String> {
+        let changes = json::diff(original, edited);
+        let mut document = save.to_document();
+
+        for change in changes.iter() {
+            let (Some(target), Some(new)) = (document.pointer_mut(&change.pointer), &change.new)
+            else {
+                return Err(format!("{} no longer exists in the save", change.pointer));
+            };
+            *target = new.clone();
+        }
+
+        save.apply_document(&document)?;
+        Ok(format!("Applied {} edits.", changes.len()))
+    }
+}
+
+/// Render a value as a tree node, returning whether it was changed.
+fn render_value(ui: &mut egui::Ui, key: &str, value: &mut Value, pointer: String) -> bool {
+    let mut changed = false;
+
+    match value {
+        Value::Object(map) => {
+            CollapsingHeader::new(format!("{} {{{}}}", key, map.len()))
+                .id_source(&pointer)
+                .show(ui, |ui| {
+                    for (key, child) in map.iter_mut() {
+                        let pointer = format!("{}/{}", pointer, json::escape(key));
+                        changed |= render_value(ui, key, child, pointer);
+                    }
+                });
+        }
+        Value::Array(array) => {
+            CollapsingHeader::new(format!("{} [{}]", key, array.len()))
+                .id_source(&pointer)
+                .show(ui, |ui| {
+                    for (i, child) in array.iter_mut().enumerate() {
+                        let pointer = format!("{}/{}", pointer, i);
+                        changed |= render_value(ui, &i.to_string(), child, pointer);
+                    }
+                });
+        }
+        _ => {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(key).monospace());
+                changed = render_scalar(ui, value);
+            });
+        }
+    }
+
+    changed
+}
+
+/// Render an editor for a scalar value, returning whether it was changed.
+fn render_scalar(ui: &mut egui::Ui, value: &mut Value) -> bool {
+    match value {
+        Value::Bool(b) => ui.checkbox(b, "").changed(),
+        // floats the game couldn't compute are stored as "NaN"
+        Value::String(s) if s == "NaN" => {
+            ui.label("NaN");
+            if ui.small_button("Set number").clicked() {
+                *value = Value::from(0.0);
+                return true;
+            }
+            false
+        }
+        Value::String(s) => ui
+            .add(TextEdit::singleline(s).desired_width(300.0))
+            .changed(),
+        Value::Number(n) if n.is_i64() || n.is_u64() => {
+            let mut i = n.as_i64().unwrap_or(i64::MAX);
+            if ui.add(DragValue::new(&mut i)).changed() {
+                *value = Value::from(i);
+                return true;
+            }
+            false
+        }
+        Value::Number(n) => {
+            let mut f = n.as_f64().unwrap_or_default();
+            if ui.add(DragValue::new(&mut f).speed(0.1)).changed() {
+                *value = Value::from(f);
+                return true;
+            }
+            if ui
+                .small_button("NaN")
+                .on_hover_text("Set to NaN.")
+                .clicked()
+            {
+                *value = Value::from("NaN");
+                return true;
+            }
+            false
+        }
+        _ => {
+            ui.label("null");
+            false
+        }
+    }
+}
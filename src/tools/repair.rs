@@ -0,0 +1,87 @@
+use egui::Button;
+
+use crate::{
+    repair::{self, Fix, Repair},
+    save::Save,
+};
+
+use super::{Params, SaveTool};
+
+#[derive(Debug, Clone)]
+pub struct ToolRepair {
+    /// Which repairs to make, in the order of [`Repair::ALL`].
+    enabled: Vec<(Repair, bool)>,
+    fixes: Option<Vec<Fix>>,
+}
+
+impl SaveTool for ToolRepair {
+    const NAME: &'static str = "Repair";
+    const DESCRIPTION: &'static str = "Fix common save corruption. \
+        Takes only=<repair>,<repair> to pick repairs, defaulting to all but conflicting-flags.";
+
+    fn new(_save: &Save) -> Self {
+        Self {
+            // files are recovered before a save is loaded, from the command line
+            enabled: Repair::ALL
+                .into_iter()
+                .filter(|r| *r != Repair::TruncatedFiles)
+                .map(|r| (r, Repair::DEFAULT.contains(&r)))
+                .collect(),
+            fixes: None,
+        }
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&["only"])?;
+        let repairs = match params.get::<String>("only")? {
+            Some(only) => only
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<Repair>, _>>()?,
+            None => Repair::DEFAULT.to_vec(),
+        };
+
+        Ok(repair::repair_save(save, &repairs)
+            .into_iter()
+            .filter(|fix| fix.applied)
+            .map(|fix| fix.to_string())
+            .collect())
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                for (repair, enabled) in self.enabled.iter_mut() {
+                    ui.checkbox(enabled, repair.to_string())
+                        .on_hover_text(repair.description());
+                }
+            });
+
+            let repairs = self
+                .enabled
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(repair, _)| *repair)
+                .collect::<Vec<_>>();
+
+            if ui
+                .add_enabled(!repairs.is_empty(), Button::new("Repair"))
+                .clicked()
+            {
+                self.fixes = Some(repair::repair_save(save, &repairs));
+            }
+
+            match &self.fixes {
+                Some(fixes) if fixes.is_empty() => {
+                    ui.label("Nothing needed fixing.");
+                }
+                Some(fixes) => {
+                    for fix in fixes.iter() {
+                        ui.label(fix.to_string());
+                    }
+                }
+                None => (),
+            }
+        });
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use egui::{RichText, ScrollArea, TextEdit};
+
+use crate::{save::Save, script::Script};
+
+use super::{Params, SaveTool};
+
+const EXAMPLE: &str = "\
+// list every actor within 200m of the player, without changing anything
+if !is_def_var(\"player_position\") {
+    print(\"the player's position is unknown\");
+} else {
+    for actor in actors {
+        if actor.Position != () {
+            let d = distance(actor.Position, player_position);
+            if d < 200.0 {
+                print(`type ${actor.TypeId}, state ${actor.State}, ${d.round()}m away`);
+            }
+        }
+    }
+}";
+
+#[derive(Debug, Clone)]
+pub struct ToolScript {
+    source: String,
+    /// What runs printed and changed, oldest first.
+    log: Vec<String>,
+}
+
+impl SaveTool for ToolScript {
+    const NAME: &'static str = "Script";
+    const DESCRIPTION: &'static str = "Run a Rhai script on the save. Takes file=<script>.";
+
+    fn new(_save: &Save) -> Self {
+        Self {
+            source: EXAMPLE.to_owned(),
+            log: vec![],
+        }
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&["file"])?;
+        let file = params
+            .get::<PathBuf>("file")?
+            .ok_or("file=<script> is required")?;
+
+        let script = Script::read(&file)?;
+        let result = script.run(save, false);
+
+        let mut done = script.take_output();
+        done.push(format!(
+            "ran {} ({} changes)",
+            file.display(),
+            result?.len()
+        ));
+        Ok(done)
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.add(
+                TextEdit::multiline(&mut self.source)
+                    .code_editor()
+                    .desired_rows(8)
+                    .desired_width(f32::INFINITY),
+            )
+            .on_hover_text(
+                "A Rhai script. The save is available as save, and parts of it as actors, \
+                kill_stats, player_stats and game_state. player_position is set if the \
+                player's position is known.",
+            );
+
+            ui.horizontal(|ui| {
+                if ui.button("Run").clicked() {
+                    self.run(save, false);
+                }
+                if ui
+                    .button("Dry run")
+                    .on_hover_text("Show what the script would change without changing the save.")
+                    .clicked()
+                {
+                    self.run(save, true);
+                }
+                if ui.button("Clear").clicked() {
+                    self.log.clear();
+                }
+            });
+
+            ScrollArea::vertical()
+                .id_source("tool_script_log")
+                .max_height(200.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in self.log.iter() {
+                        ui.label(RichText::new(line).monospace());
+                    }
+                });
+        });
+    }
+}
+
+impl ToolScript {
+    fn run(&mut self, save: &mut Save, dry_run: bool) {
+        let result = Script::compile(&self.source).and_then(|script| {
+            let result = script.run(save, dry_run);
+            self.log.extend(script.take_output());
+            result
+        });
+
+        match result {
+            Ok(changes) => {
+                self.log.extend(changes.iter().map(|c| c.to_string()));
+                self.log.push(format!(
+                    "{} changes{}.",
+                    changes.len(),
+                    if dry_run { " (dry run)" } else { "" }
+                ));
+            }
+            Err(e) => self.log.push(e),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use egui::{Button, DragValue};
+
+use crate::save::{item_info, item_name, ItemBlock, Save, StorageContainer, ITEMS};
+
+use super::{Params, SaveTool};
+
+/// A change to a storage container requested from the UI.
+enum StorageAction {
+    Add(usize),
+    Remove(usize, usize, usize),
+    Fill(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolStorage {
+    add_item: u32,
+    add_count: u32,
+    status: Option<String>,
+}
+
+impl SaveTool for ToolStorage {
+    const NAME: &'static str = "Storage";
+    const DESCRIPTION: &'static str = "Items in storage containers. Takes add with item=<id> \
+        and count=<count>, or fill with an optional item=<id> for empty slots, each for every \
+        container or just container=<id>.";
+
+    fn new(_save: &Save) -> Self {
+        Self {
+            add_item: ITEMS[0].id,
+            add_count: 1,
+            status: None,
+        }
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        let storages = &save.storages.as_ref()?.data.storages.storages;
+        Some(format!("{} containers", storages.len()))
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&["add", "fill", "item", "count", "container"])?;
+        if !params.has("add") && !params.has("fill") {
+            return Err("add or fill is required".to_owned());
+        }
+
+        let item = params.get::<u32>("item")?;
+        let count = params.get::<u32>("count")?.unwrap_or(1);
+        let id = params.get::<u32>("container")?;
+        if params.has("add") && item.is_none() {
+            return Err("add needs item=<id>".to_owned());
+        }
+
+        let Some(storage) = &mut save.storages else {
+            return Ok(vec!["save has no storage".to_owned()]);
+        };
+        let storages = &mut storage.data.storages.storages;
+
+        let mut done = vec![];
+        for container in storages
+            .iter_mut()
+            .filter(|c| id.map_or(true, |id| c.id == id))
+        {
+            if let (true, Some(item)) = (params.has("add"), item) {
+                let added = Self::add_items(container, item, count);
+                done.push(format!(
+                    "added {} x {} to storage #{}",
+                    added,
+                    item_name(item),
+                    container.id
+                ));
+            }
+            if params.has("fill") {
+                let filled = Self::fill_to_capacity(container, item);
+                done.push(format!(
+                    "filled {} slots of storage #{}",
+                    filled, container.id
+                ));
+            }
+        }
+
+        match (done.is_empty(), id) {
+            (true, Some(id)) => Err(format!("no storage container {}", id)),
+            (true, None) => Err("save has no storage containers".to_owned()),
+            _ => Ok(done),
+        }
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        let storages = match &mut save.storages {
+            Some(storages) => &mut storages.data.storages.storages,
+            None => {
+                ui.label("This save has no storage.");
+                return;
+            }
+        };
+
+        let mut action = None;
+
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Item to add");
+                egui::ComboBox::from_id_source("tool_storage_item")
+                    .selected_text(item_name(self.add_item))
+                    .show_ui(ui, |ui| {
+                        for item in ITEMS.iter() {
+                            ui.selectable_value(&mut self.add_item, item.id, item.name);
+                        }
+                    });
+                ui.add(DragValue::new(&mut self.add_item).prefix("id: "));
+                ui.add(DragValue::new(&mut self.add_count).clamp_range(1..=u32::MAX));
+            });
+
+            ui.collapsing(format!("Containers ({})", storages.len()), |ui| {
+                for (i, container) in storages.iter_mut().enumerate() {
+                    ui.push_id(i, |ui| {
+                        ui.collapsing(
+                            format!("Storage #{} at {}", container.id, container.position),
+                            |ui| {
+                                for (j, slot) in container.storages.iter_mut().enumerate() {
+                                    if slot.item_blocks.is_empty() {
+                                        ui.weak(format!("Slot {}: empty", j + 1));
+                                    }
+
+                                    for (k, block) in slot.item_blocks.iter_mut().enumerate() {
+                                        ui.horizontal(|ui| {
+                                            ui.label(format!(
+                                                "Slot {}: {}",
+                                                j + 1,
+                                                item_name(block.item_id)
+                                            ));
+                                            ui.add(DragValue::new(&mut block.total_count));
+                                            if ui.small_button("Remove").clicked() {
+                                                action = Some(StorageAction::Remove(i, j, k));
+                                            }
+                                        });
+                                    }
+                                }
+
+                                ui.horizontal(|ui| {
+                                    if ui.add(Button::new("Add item")).clicked() {
+                                        action = Some(StorageAction::Add(i));
+                                    }
+                                    if ui
+                                        .add(Button::new("Fill to capacity"))
+                                        .on_hover_text(
+                                            "Fill every stack to its maximum size, and every \
+                                            empty slot with the item to add.",
+                                        )
+                                        .clicked()
+                                    {
+                                        action = Some(StorageAction::Fill(i));
+                                    }
+                                });
+                            },
+                        );
+                    });
+                }
+            });
+
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+
+        match action {
+            Some(StorageAction::Add(i)) => {
+                self.status = Some(
+                    match Self::add_items(&mut storages[i], self.add_item, self.add_count) {
+                        0 => "There is no room for it in this container.".to_owned(),
+                        added => format!("Added {} x {}.", added, item_name(self.add_item)),
+                    },
+                );
+            }
+            Some(StorageAction::Remove(i, j, k)) => {
+                let block = storages[i].storages[j].item_blocks.remove(k);
+                self.status = Some(format!(
+                    "Removed {} x {}.",
+                    block.total_count,
+                    item_name(block.item_id)
+                ));
+            }
+            Some(StorageAction::Fill(i)) => {
+                let filled = Self::fill_to_capacity(&mut storages[i], Some(self.add_item));
+                self.status = Some(format!("Filled {} slots.", filled));
+            }
+            None => (),
+        }
+    }
+}
+
+impl ToolStorage {
+    /// Add items to a container, stacking onto a slot holding the same item or taking the
+    /// first empty slot, up to the item's maximum stack size. Returns how many were added.
+    pub fn add_items(container: &mut StorageContainer, item_id: u32, count: u32) -> u32 {
+        let max_stack = item_info(item_id).map_or(u32::MAX, |info| info.max_stack);
+
+        if let Some(block) = container
+            .storages
+            .iter_mut()
+            .flat_map(|slot| slot.item_blocks.iter_mut())
+            .find(|b| b.item_id == item_id && b.total_count < max_stack)
+        {
+            let before = block.total_count;
+            block.total_count = before.saturating_add(count).min(max_stack);
+            return block.total_count - before;
+        }
+
+        if let Some(slot) = container
+            .storages
+            .iter_mut()
+            .find(|s| s.item_blocks.is_empty())
+        {
+            let count = count.min(max_stack);
+            slot.item_blocks.push(ItemBlock::new(item_id, count));
+            return count;
+        }
+
+        0
+    }
+
+    /// Fill every known item stack in a container to its maximum size, and every empty slot
+    /// with a full stack of an item if given, returning how many slots changed. Empty slots
+    /// are left alone if the item's stack size isn't known.
+    pub fn fill_to_capacity(container: &mut StorageContainer, item_id: Option<u32>) -> usize {
+        let mut filled = 0;
+
+        for slot in container.storages.iter_mut() {
+            if slot.item_blocks.is_empty() {
+                if let Some(info) = item_id.and_then(item_info) {
+                    slot.item_blocks
+                        .push(ItemBlock::new(info.id, info.max_stack));
+                    filled += 1;
+                    continue;
+                }
+            }
+
+            for block in slot.item_blocks.iter_mut() {
+                if let Some(info) = item_info(block.item_id) {
+                    if block.total_count < info.max_stack {
+                        block.total_count = info.max_stack;
+                        filled += 1;
+                    }
+                }
+            }
+        }
+
+        filled
+    }
+}
@@ -0,0 +1,66 @@
+use egui::RichText;
+
+use crate::{
+    save::Save,
+    validate::{self, Issue, Severity},
+};
+
+use super::SaveTool;
+
+#[derive(Debug, Clone)]
+pub struct ToolValidate {
+    /// The save's problems as of its last change.
+    issues: Vec<Issue>,
+}
+
+impl SaveTool for ToolValidate {
+    const NAME: &'static str = "Problems";
+    const DESCRIPTION: &'static str = "Problems in the save the game may not cope with.";
+
+    fn new(save: &Save) -> Self {
+        Self {
+            issues: validate::validate(save),
+        }
+    }
+
+    fn save_changed(&mut self, save: &Save) {
+        self.issues = validate::validate(save);
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        Some(summary(&validate::validate(save)))
+    }
+
+    fn current_status(&self, _save: &Save) -> Option<String> {
+        Some(summary(&self.issues))
+    }
+
+    fn render(&mut self, _save: &mut Save, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            if self.issues.is_empty() {
+                ui.label("No problems found.");
+            }
+
+            for issue in self.issues.iter() {
+                let color = match issue.severity {
+                    Severity::Error => ui.visuals().error_fg_color,
+                    Severity::Warning => ui.visuals().warn_fg_color,
+                };
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.colored_label(color, RichText::new(&issue.path).monospace());
+                    ui.label(&issue.message);
+                });
+            }
+        });
+    }
+}
+
+/// How many errors and warnings there are, e.g. `1 errors, 2 warnings`.
+fn summary(issues: &[Issue]) -> String {
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .count();
+    format!("{} errors, {} warnings", errors, issues.len() - errors)
+}
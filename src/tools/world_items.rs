@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use egui::Button;
+
+use crate::save::{item_name, Save, Vector3, WorldItemManagerInner};
+
+use super::{area_params, render_area, Params, SaveTool};
+
+#[derive(Debug, Clone)]
+pub struct ToolWorldItems {
+    center: Vector3,
+    radius: f32,
+    status: Option<String>,
+}
+
+impl SaveTool for ToolWorldItems {
+    const NAME: &'static str = "World items";
+    const DESCRIPTION: &'static str = "Items placed or dropped in the world. Takes \
+        respawn-pickups, or delete with center=x,y,z (defaults to the player) and radius=<meters>.";
+
+    fn new(save: &Save) -> Self {
+        Self {
+            center: save.player_position().unwrap_or_default(),
+            radius: 10.0,
+            status: None,
+        }
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        let manager = &save.world_items.as_ref()?.data.world_item_manager;
+        Some(format!(
+            "{} items, {} collected pickups",
+            manager.world_items.len(),
+            manager.collected_pickups.len()
+        ))
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&["delete", "respawn-pickups", "center", "radius"])?;
+        if !params.has("delete") && !params.has("respawn-pickups") {
+            return Err("delete or respawn-pickups is required".to_owned());
+        }
+
+        let area = params
+            .has("delete")
+            .then(|| area_params(save, params))
+            .transpose()?;
+        let Some(manager) = &mut save.world_items else {
+            return Ok(vec!["save has no world items".to_owned()]);
+        };
+        let manager = &mut manager.data.world_item_manager;
+
+        let mut done = vec![];
+        if let Some((center, radius)) = area {
+            let tool = Self {
+                center,
+                radius,
+                status: None,
+            };
+            let deleted = tool.delete_in_radius(manager);
+            done.push(format!("deleted {} items", deleted));
+        }
+        if params.has("respawn-pickups") {
+            done.push(format!(
+                "respawned {} pickups",
+                manager.collected_pickups.len()
+            ));
+            manager.collected_pickups.clear();
+        }
+
+        Ok(done)
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        let player_position = save.player_position();
+        let manager = match &mut save.world_items {
+            Some(manager) => &mut manager.data.world_item_manager,
+            None => {
+                ui.label("This save has no world items.");
+                return;
+            }
+        };
+
+        let mut move_to_player = None;
+        let mut delete = None;
+
+        ui.vertical(|ui| {
+            // group item indices by item type
+            let mut types = BTreeMap::<u32, Vec<usize>>::new();
+            for (i, item) in manager.world_items.iter().enumerate() {
+                types.entry(item.item_id).or_default().push(i);
+            }
+
+            ui.collapsing(format!("Items ({})", manager.world_items.len()), |ui| {
+                egui::ScrollArea::vertical()
+                    .id_source("tool_world_items_list")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for (item_id, indices) in types.iter() {
+                            ui.collapsing(
+                                format!("{} ({})", item_name(*item_id), indices.len()),
+                                |ui| {
+                                    for &i in indices.iter() {
+                                        let item = &manager.world_items[i];
+                                        ui.horizontal(|ui| {
+                                            ui.label(format!(
+                                                "{} ({:.0}m away), state {}",
+                                                item.position,
+                                                item.position.distance(&self.center),
+                                                item.state
+                                            ));
+
+                                            if ui
+                                                .add_enabled(
+                                                    player_position.is_some(),
+                                                    Button::new("Move to player").small(),
+                                                )
+                                                .clicked()
+                                            {
+                                                move_to_player = Some(i);
+                                            }
+
+                                            if ui.small_button("Delete").clicked() {
+                                                delete = Some(i);
+                                            }
+                                        });
+                                    }
+                                },
+                            );
+                        }
+                    });
+            });
+
+            render_area(ui, player_position, &mut self.center, &mut self.radius);
+
+            ui.horizontal(|ui| {
+                if ui.button("Delete in radius").clicked() {
+                    let deleted = self.delete_in_radius(manager);
+                    self.status = Some(format!("Deleted {} items.", deleted));
+                }
+
+                if ui
+                    .add_enabled(
+                        !manager.collected_pickups.is_empty(),
+                        Button::new("Respawn pickups"),
+                    )
+                    .on_hover_text("Forget collected pickups so they appear in the world again.")
+                    .clicked()
+                {
+                    self.status = Some(format!(
+                        "Respawned {} pickups.",
+                        manager.collected_pickups.len()
+                    ));
+                    manager.collected_pickups.clear();
+                }
+            });
+
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+
+        if let (Some(i), Some(position)) = (move_to_player, player_position) {
+            let item = &mut manager.world_items[i];
+            // drop the item just above the player so it doesn't clip into the ground
+            item.position = Vector3::new(position.x, position.y + 1.0, position.z);
+            self.status = Some(format!("Moved {} to the player.", item_name(item.item_id)));
+        }
+
+        if let Some(i) = delete {
+            let item = manager.world_items.remove(i);
+            self.status = Some(format!("Deleted {}.", item_name(item.item_id)));
+        }
+    }
+}
+
+impl ToolWorldItems {
+    /// Delete every item within the radius, returning how many were deleted.
+    fn delete_in_radius(&self, manager: &mut WorldItemManagerInner) -> usize {
+        let len = manager.world_items.len();
+        manager
+            .world_items
+            .retain(|i| i.position.distance(&self.center) > self.radius);
+        len - manager.world_items.len()
+    }
+}
@@ -0,0 +1,272 @@
+use egui::{Button, DragValue};
+
+use crate::{
+    save::{Save, Vector3, ZipLine, DEFAULT_MAX_ZIPLINE_LENGTH},
+    validate::Issue,
+};
+
+use super::{Params, SaveTool};
+
+const DEFAULT_TOLERANCE: f32 = 3.0;
+
+#[derive(Debug, Clone)]
+pub struct ToolZipLines {
+    /// How close an endpoint must be to a structure to count as attached, in meters.
+    tolerance: f32,
+    /// Ziplines longer than this count as broken, in meters.
+    max_length: f32,
+    /// Structure positions as of the save's last change.
+    structures: Vec<Vector3>,
+    /// Whether each zipline is attached, kept up to date with the save and tolerance.
+    attached: Vec<bool>,
+    status: Option<String>,
+}
+
+impl SaveTool for ToolZipLines {
+    const NAME: &'static str = "Ziplines";
+    const DESCRIPTION: &'static str = "Ziplines the player has placed. Takes delete-broken \
+        with an optional max-length=<meters> (500 by default), or delete-unattached with an \
+        optional tolerance=<meters>. Ziplines hung from trees count as unattached.";
+
+    fn new(save: &Save) -> Self {
+        let mut tool = Self {
+            tolerance: DEFAULT_TOLERANCE,
+            max_length: DEFAULT_MAX_ZIPLINE_LENGTH,
+            structures: vec![],
+            attached: vec![],
+            status: None,
+        };
+        tool.save_changed(save);
+        tool
+    }
+
+    fn save_changed(&mut self, save: &Save) {
+        self.structures = save.structure_positions();
+        self.update_attached(save.zip_lines.as_ref().map_or(&[], |manager| {
+            manager.data.zip_line_manager.zip_lines.as_slice()
+        }));
+    }
+
+    fn status(save: &Save) -> Option<String> {
+        let zip_lines = &save.zip_lines.as_ref()?.data.zip_line_manager.zip_lines;
+        let broken = zip_lines
+            .iter()
+            .filter(|z| z.is_broken(DEFAULT_MAX_ZIPLINE_LENGTH))
+            .count();
+        Some(format!("{} ziplines, {} broken", zip_lines.len(), broken))
+    }
+
+    fn apply(save: &mut Save, params: &Params) -> Result<Vec<String>, String> {
+        params.expect(&[
+            "delete-broken",
+            "delete-unattached",
+            "tolerance",
+            "max-length",
+        ])?;
+        if !params.has("delete-broken") && !params.has("delete-unattached") {
+            return Err("delete-broken or delete-unattached is required".to_owned());
+        }
+
+        let tool = Self {
+            tolerance: params.get("tolerance")?.unwrap_or(DEFAULT_TOLERANCE),
+            max_length: params
+                .get("max-length")?
+                .unwrap_or(DEFAULT_MAX_ZIPLINE_LENGTH),
+            structures: vec![],
+            attached: vec![],
+            status: None,
+        };
+        let structures = save.structure_positions();
+        let Some(manager) = &mut save.zip_lines else {
+            return Ok(vec!["save has no ziplines".to_owned()]);
+        };
+        if params.has("delete-unattached") && structures.is_empty() {
+            // every zipline would count as unattached, including those hung from trees
+            return Err("delete-unattached: the save has no structures to attach to".to_owned());
+        }
+        let zip_lines = &mut manager.data.zip_line_manager.zip_lines;
+
+        let mut done = vec![];
+        if params.has("delete-broken") {
+            let len = zip_lines.len();
+            zip_lines.retain(|z| !z.is_broken(tool.max_length));
+            done.push(format!("deleted {} broken ziplines", len - zip_lines.len()));
+        }
+        if params.has("delete-unattached") {
+            let len = zip_lines.len();
+            zip_lines.retain(|z| tool.is_attached(z, &structures));
+            done.push(format!(
+                "deleted {} unattached ziplines",
+                len - zip_lines.len()
+            ));
+        }
+
+        Ok(done)
+    }
+
+    fn validate(save: &Save) -> Vec<Issue> {
+        let Some(manager) = &save.zip_lines else {
+            return vec![];
+        };
+
+        manager
+            .data
+            .zip_line_manager
+            .zip_lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, zip_line)| {
+                let path = format!("ZipLineManager.ZipLines[{}]", i);
+                // the length limit is an estimate, so going past it is only a warning
+                match zip_line.problem(f32::INFINITY) {
+                    Some(problem) => Some(Issue::error(
+                        path,
+                        format!(
+                            "{} to {} can't exist in-game, {}",
+                            zip_line.point_a, zip_line.point_b, problem
+                        ),
+                    )),
+                    None => Some(Issue::warning(
+                        path,
+                        format!(
+                            "{} to {} may not exist in-game, {}",
+                            zip_line.point_a,
+                            zip_line.point_b,
+                            zip_line.problem(DEFAULT_MAX_ZIPLINE_LENGTH)?
+                        ),
+                    )),
+                }
+            })
+            .collect()
+    }
+
+    fn render(&mut self, save: &mut Save, ui: &mut egui::Ui) {
+        let zip_lines = match &mut save.zip_lines {
+            Some(manager) => &mut manager.data.zip_line_manager.zip_lines,
+            None => {
+                ui.label("This save has no ziplines.");
+                return;
+            }
+        };
+
+        let mut delete = None;
+
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Attach tolerance");
+                if ui
+                    .add(
+                        DragValue::new(&mut self.tolerance)
+                            .clamp_range(0.0..=50.0)
+                            .suffix("m"),
+                    )
+                    .changed()
+                {
+                    self.update_attached(zip_lines);
+                }
+
+                ui.label("Longest");
+                ui.add(
+                    DragValue::new(&mut self.max_length)
+                        .clamp_range(1.0..=f32::MAX)
+                        .suffix("m"),
+                )
+                .on_hover_text(
+                    "Ziplines longer than this count as broken. The game's own limit isn't \
+                    known exactly, so raise this if a zipline you built is marked broken.",
+                );
+            });
+
+            ui.collapsing(format!("Ziplines ({})", zip_lines.len()), |ui| {
+                for (i, zip_line) in zip_lines.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "{} to {} ({:.0}m)",
+                            zip_line.point_a,
+                            zip_line.point_b,
+                            zip_line.length()
+                        ));
+
+                        if let Some(problem) = zip_line.problem(self.max_length) {
+                            ui.colored_label(ui.visuals().error_fg_color, "broken")
+                                .on_hover_text(problem);
+                        } else if !self.structures.is_empty()
+                            && !self.attached.get(i).copied().unwrap_or(true)
+                        {
+                            ui.colored_label(ui.visuals().warn_fg_color, "unattached");
+                        }
+
+                        if ui.small_button("Delete").clicked() {
+                            delete = Some(i);
+                        }
+                    });
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add(Button::new("Delete broken"))
+                    .on_hover_text("Delete ziplines with invalid endpoints or length.")
+                    .clicked()
+                {
+                    let len = zip_lines.len();
+                    zip_lines.retain(|z| !z.is_broken(self.max_length));
+                    self.status = Some(format!("Deleted {} ziplines.", len - zip_lines.len()));
+                    self.update_attached(zip_lines);
+                }
+
+                if ui
+                    .add_enabled(
+                        !self.structures.is_empty(),
+                        Button::new("Delete unattached"),
+                    )
+                    .on_hover_text(
+                        "Delete ziplines with an endpoint that isn't near a structure. Ziplines \
+                        hung from trees count as unattached too, so check the list first.",
+                    )
+                    .on_disabled_hover_text("This save has no structures to attach to.")
+                    .clicked()
+                {
+                    let len = zip_lines.len();
+                    zip_lines.retain(|z| self.is_attached(z, &self.structures));
+                    self.status = Some(format!("Deleted {} ziplines.", len - zip_lines.len()));
+                    self.update_attached(zip_lines);
+                }
+            });
+
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+        });
+
+        if let Some(i) = delete {
+            zip_lines.remove(i);
+            if i < self.attached.len() {
+                self.attached.remove(i);
+            }
+            self.status = Some("Deleted 1 zipline.".to_owned());
+        }
+    }
+}
+
+impl ToolZipLines {
+    /// Whether both endpoints of a zipline are within tolerance of a structure.
+    pub fn is_attached(&self, zip_line: &ZipLine, structures: &[Vector3]) -> bool {
+        let near_structure = |point: &Vector3| {
+            structures
+                .iter()
+                .any(|s| s.distance(point) <= self.tolerance)
+        };
+
+        near_structure(&zip_line.point_a) && near_structure(&zip_line.point_b)
+    }
+
+    /// Check which ziplines are attached to the cached structures, which is slow enough with
+    /// many of both not to do every frame.
+    fn update_attached(&mut self, zip_lines: &[ZipLine]) {
+        self.attached = zip_lines
+            .iter()
+            .map(|z| self.is_attached(z, &self.structures))
+            .collect();
+    }
+}
@@ -0,0 +1,179 @@
+//! Consistency checks for saves, reporting problems the game may not cope with.
+
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    save::{manage::PLAYER_FILES, Save, ACTOR_STATE_DEAD},
+    tools,
+};
+
+pub const GAME_STATE_PATH: &str = "GameState";
+pub const ACTORS_PATH: &str = "SaveData.VailWorldSim.Actors";
+pub const KILL_STATS_PATH: &str = "SaveData.VailWorldSim.KillStatsList";
+pub const PLAYER_STATS_PATH: &str = "SaveData.VailWorldSim.PlayerStats";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem found in a save, at a path into its document.
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        write!(f, "{:<7} {}: {}", severity, self.path, self.message)
+    }
+}
+
+#[derive(Default)]
+struct Issues(Vec<Issue>);
+
+impl Issues {
+    fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(Issue::error(path, message));
+    }
+
+    fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(Issue::warning(path, message));
+    }
+
+    /// The issues, errors first.
+    fn sorted(mut self) -> Vec<Issue> {
+        self.0
+            .sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+        self.0
+    }
+}
+
+/// Check a save folder's files, then its contents if it can be read.
+pub fn validate_folder(path: &Path) -> Vec<Issue> {
+    let mut issues = Issues::default();
+
+    for file in ["GameStateSaveData.json", "SaveData.json"] {
+        if !path.join(file).is_file() {
+            issues.error(file, "required file is missing");
+        }
+    }
+
+    for file in PLAYER_FILES.iter().chain(&["GameSetupSaveData.json"]) {
+        if !path.join(file).is_file() {
+            issues.warning(*file, "file is missing, the game will use defaults");
+        }
+    }
+
+    if issues.0.iter().all(|i| i.severity != Severity::Error) {
+        match Save::read(path.to_owned()) {
+            Ok(save) => issues.0.extend(validate(&save)),
+            Err(e) => issues.error(path.display().to_string(), e.to_string()),
+        }
+    }
+
+    issues.sorted()
+}
+
+/// Check a save's contents, including the checks of every tool.
+pub fn validate(save: &Save) -> Vec<Issue> {
+    let mut issues = Issues::default();
+
+    for unparsed in save.unparsed.iter() {
+        issues.warning(
+            unparsed.file,
+            format!(
+                "couldn't be read, so it is left as it is: {}",
+                unparsed.error
+            ),
+        );
+    }
+
+    validate_game_state(save, &mut issues);
+    validate_actors(save, &mut issues);
+    validate_kill_stats(save, &mut issues);
+    issues.0.extend(tools::validate(save));
+
+    issues.sorted()
+}
+
+fn validate_game_state(save: &Save, issues: &mut Issues) {
+    let state = &save.game_state.data.game_state;
+
+    let time = [
+        ("GameDays", state.game_days, i32::MAX),
+        ("GameHours", state.game_hours, 23),
+        ("GameMinutes", state.game_minutes, 59),
+        ("GameSeconds", state.game_seconds, 59),
+        ("GameMilliseconds", state.game_milliseconds, 999),
+    ];
+    for (key, value, max) in time {
+        if !(0..=max).contains(&value) {
+            issues.error(
+                format!("{}.{}", GAME_STATE_PATH, key),
+                format!("{} is out of range 0 to {}", value, max),
+            );
+        }
+    }
+}
+
+fn validate_actors(save: &Save, issues: &mut Issues) {
+    let actors = &save.save_data.data.vail_world_sim.actors;
+
+    for (i, actor) in actors.iter().enumerate() {
+        let Some(stats) = &actor.stats else {
+            continue;
+        };
+
+        let path = format!("{}[{}].Stats.Health", ACTORS_PATH, i);
+        if actor.state == ACTOR_STATE_DEAD {
+            continue;
+        } else if stats.health.is_nan() {
+            issues.error(path, "health of a living actor is NaN");
+        } else if stats.health < 0.0 {
+            issues.warning(
+                path,
+                format!("health of a living actor is {}", stats.health),
+            );
+        }
+    }
+}
+
+fn validate_kill_stats(save: &Save, issues: &mut Issues) {
+    let world = &save.save_data.data.vail_world_sim;
+
+    for (i, kill) in world.kill_stats_list.iter().enumerate() {
+        if kill.player_killed < 0 {
+            issues.error(
+                format!("{}[{}].PlayerKilled", KILL_STATS_PATH, i),
+                format!("kill count {} is negative", kill.player_killed),
+            );
+        }
+    }
+}